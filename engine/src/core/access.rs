@@ -0,0 +1,323 @@
+use crate::core::types::ShareAccessError;
+use iroh::endpoint::Connection;
+use iroh::protocol::{AcceptError, ProtocolHandler};
+use iroh::{Endpoint, EndpointAddr, EndpointId};
+use iroh_blobs::protocol::ERR_PERMISSION;
+use rand::RngExt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::ErrorKind;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::timeout;
+
+/// ALPN of the protocol through which receivers prove they know the passphrase of a share.
+/// The sender writes a random 32 byte challenge, the receiver answers with a keyed hash of the
+/// challenge and its endpoint ID, and the sender replies with 1 if the proof matched, 0 if not
+/// and 2 if the receiver is not allowed at all. The passphrase itself never leaves either side.
+pub const AUTH_ALPN: &[u8] = b"sendme/auth/1";
+
+const PASSPHRASE_KEY_CONTEXT: &str = "sendme share passphrase 2026-10 proof key";
+
+/// How long a sender waits before rejecting a wrong proof, to slow down guessing.
+const WRONG_PROOF_DELAY: Duration = Duration::from_secs(1);
+
+/// Sent by the metadata protocol instead of the metadata of a share the receiver may not see.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MetadataDenied {
+    pub denied: ShareAccessError,
+}
+
+/// Key derived from a share passphrase.
+#[derive(Clone)]
+struct PassphraseKey([u8; 32]);
+
+impl PassphraseKey {
+    fn new(passphrase: &str) -> Self {
+        Self(blake3::derive_key(
+            PASSPHRASE_KEY_CONTEXT,
+            passphrase.as_bytes(),
+        ))
+    }
+
+    /// Proof that `receiver` knows the passphrase, valid for one challenge only.
+    fn proof(&self, challenge: &[u8; 32], receiver: &EndpointId) -> blake3::Hash {
+        let mut hasher = blake3::Hasher::new_keyed(&self.0);
+        hasher.update(challenge);
+        hasher.update(receiver.as_bytes());
+        hasher.finalize()
+    }
+}
+
+impl std::fmt::Debug for PassphraseKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PassphraseKey(..)")
+    }
+}
+
+/// # Description
+/// Decides which receivers may fetch a share: those in the allow-list if there is one, and
+/// with a passphrase only once they passed the challenge of [`AUTH_ALPN`] while the share is
+/// running. Receivers the app denied in a `connection-request` are refused from then on.
+/// Clones share their state.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ShareAccess {
+    inner: Arc<AccessState>,
+}
+
+#[derive(Debug, Default)]
+struct AccessState {
+    allowed: Option<HashSet<EndpointId>>,
+    passphrase: Option<PassphraseKey>,
+    authenticated: Mutex<HashSet<EndpointId>>,
+    confirmed: Mutex<HashMap<EndpointId, bool>>,
+}
+
+impl ShareAccess {
+    pub(crate) fn new(allowed: Option<HashSet<EndpointId>>, passphrase: Option<&str>) -> Self {
+        Self {
+            inner: Arc::new(AccessState {
+                allowed,
+                passphrase: passphrase.map(PassphraseKey::new),
+                authenticated: Mutex::default(),
+                confirmed: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Whether `endpoint_id` may fetch the share now, and if not, why.
+    pub(crate) fn check(&self, endpoint_id: &EndpointId) -> Result<(), ShareAccessError> {
+        if !self.is_listed(endpoint_id) || self.confirmed(endpoint_id) == Some(false) {
+            return Err(ShareAccessError::NotAllowed);
+        }
+        if self.inner.passphrase.is_some()
+            && !self
+                .inner
+                .authenticated
+                .lock()
+                .expect("poisoned")
+                .contains(endpoint_id)
+        {
+            return Err(ShareAccessError::PasswordRequired);
+        }
+        Ok(())
+    }
+
+    /// The app's answer to a `connection-request` of this receiver, if it gave one.
+    pub(crate) fn confirmed(&self, endpoint_id: &EndpointId) -> Option<bool> {
+        self.inner
+            .confirmed
+            .lock()
+            .expect("poisoned")
+            .get(endpoint_id)
+            .copied()
+    }
+
+    /// Remembers the app's answer for the rest of the share.
+    pub(crate) fn confirm(&self, endpoint_id: EndpointId, accepted: bool) {
+        self.inner
+            .confirmed
+            .lock()
+            .expect("poisoned")
+            .insert(endpoint_id, accepted);
+    }
+
+    fn is_listed(&self, endpoint_id: &EndpointId) -> bool {
+        self.inner
+            .allowed
+            .as_ref()
+            .is_none_or(|allowed| allowed.contains(endpoint_id))
+    }
+}
+
+/// Checks the passphrase proofs of receivers, see [`AUTH_ALPN`].
+#[derive(Debug, Clone)]
+pub(crate) struct AuthProtocol {
+    pub access: ShareAccess,
+}
+
+impl ProtocolHandler for AuthProtocol {
+    /// # Description
+    /// Handles incoming connections on the auth protocol.
+    /// It reads a request marker (1 byte), sends a random challenge, checks the proof the client answers with and replies with 1 (accepted) or 0 (rejected). An accepted endpoint may fetch the share from then on.
+    async fn accept(&self, connection: Connection) -> Result<(), AcceptError> {
+        let (mut send_stream, mut recv_stream) =
+            match timeout(Duration::from_secs(30), connection.accept_bi()).await {
+                Ok(Ok(streams)) => streams,
+                Ok(Err(err)) => return Err(err.into()),
+                Err(_) => {
+                    tracing::debug!("auth accept_bi timeout (benign)");
+                    return Ok(());
+                }
+            };
+
+        let mut req = [0u8; 1];
+        timeout(Duration::from_secs(10), recv_stream.read_exact(&mut req))
+            .await
+            .map_err(|_| timed_out("auth request read timeout"))?
+            .map_err(AcceptError::from_err)?;
+
+        let challenge = rand::rng().random::<[u8; 32]>();
+        send_stream
+            .write_all(&challenge)
+            .await
+            .map_err(AcceptError::from_err)?;
+
+        let mut proof = [0u8; 32];
+        timeout(Duration::from_secs(10), recv_stream.read_exact(&mut proof))
+            .await
+            .map_err(|_| timed_out("auth proof read timeout"))?
+            .map_err(AcceptError::from_err)?;
+
+        let receiver = connection.remote_id();
+        // `blake3::Hash` compares in constant time.
+        let reply =
+            if !self.access.is_listed(&receiver) {
+                tracing::info!(%receiver, "receiver is not allowed");
+                2
+            } else if self.access.inner.passphrase.as_ref().is_none_or(|key| {
+                key.proof(&challenge, &receiver) == blake3::Hash::from_bytes(proof)
+            }) {
+                self.access
+                    .inner
+                    .authenticated
+                    .lock()
+                    .expect("poisoned")
+                    .insert(receiver);
+                tracing::info!(%receiver, "receiver authenticated");
+                1
+            } else {
+                tracing::warn!(%receiver, "receiver sent a wrong passphrase proof");
+                tokio::time::sleep(WRONG_PROOF_DELAY).await;
+                0
+            };
+
+        send_stream
+            .write_all(&[reply])
+            .await
+            .map_err(AcceptError::from_err)?;
+        send_stream.finish().map_err(AcceptError::from_err)?;
+
+        let mut eof_buf = [0u8; 1];
+        let _ = timeout(Duration::from_secs(30), recv_stream.read(&mut eof_buf)).await;
+
+        Ok(())
+    }
+}
+
+fn timed_out(message: &'static str) -> AcceptError {
+    AcceptError::from_err(std::io::Error::new(ErrorKind::TimedOut, message))
+}
+
+/// # Description
+/// Proves to the sender at `addr` that this endpoint knows the passphrase of its share.
+/// # Returns
+/// `ShareAccessError::WrongPassword` or `ShareAccessError::NotAllowed` inside the error if the
+/// sender rejected the proof.
+pub(crate) async fn authenticate(
+    endpoint: &Endpoint,
+    addr: &EndpointAddr,
+    passphrase: &str,
+) -> anyhow::Result<()> {
+    let connection = timeout(
+        Duration::from_secs(15),
+        endpoint.connect(addr.clone(), AUTH_ALPN),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("auth connect timeout"))??;
+    let (mut send_stream, mut recv_stream) = timeout(Duration::from_secs(20), connection.open_bi())
+        .await
+        .map_err(|_| anyhow::anyhow!("auth open_bi timeout"))??;
+    send_stream.write_all(&[1]).await?;
+
+    let mut challenge = [0u8; 32];
+    timeout(
+        Duration::from_secs(20),
+        recv_stream.read_exact(&mut challenge),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("auth challenge read timeout"))??;
+    let proof = PassphraseKey::new(passphrase).proof(&challenge, &endpoint.id());
+    send_stream.write_all(proof.as_bytes()).await?;
+
+    let mut reply = [0u8; 1];
+    timeout(Duration::from_secs(20), recv_stream.read_exact(&mut reply))
+        .await
+        .map_err(|_| anyhow::anyhow!("auth reply read timeout"))??;
+    let _ = send_stream.finish();
+
+    match reply[0] {
+        1 => Ok(()),
+        2 => Err(ShareAccessError::NotAllowed.into()),
+        _ => Err(ShareAccessError::WrongPassword.into()),
+    }
+}
+
+/// Tells apart a connection the sender refused from other failures of a request on it.
+pub(crate) fn refused_access(connection: &Connection) -> bool {
+    use iroh::endpoint::ConnectionError;
+
+    matches!(
+        connection.close_reason(),
+        Some(ConnectionError::ApplicationClosed(close)) if close.error_code == ERR_PERMISSION
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proofs_depend_on_passphrase_challenge_and_receiver() {
+        let receiver = iroh::SecretKey::generate().public();
+        let other = iroh::SecretKey::generate().public();
+        let key = PassphraseKey::new("correct horse");
+        let proof = key.proof(&[1; 32], &receiver);
+
+        assert_eq!(
+            proof,
+            PassphraseKey::new("correct horse").proof(&[1; 32], &receiver)
+        );
+        assert_ne!(
+            proof,
+            PassphraseKey::new("wrong horse").proof(&[1; 32], &receiver)
+        );
+        assert_ne!(proof, key.proof(&[2; 32], &receiver));
+        assert_ne!(proof, key.proof(&[1; 32], &other));
+    }
+
+    #[test]
+    fn receivers_must_be_listed_and_authenticated() {
+        let listed = iroh::SecretKey::generate().public();
+        let other = iroh::SecretKey::generate().public();
+
+        assert_eq!(ShareAccess::new(None, None).check(&other), Ok(()));
+
+        let access = ShareAccess::new(Some([listed].into()), None);
+        assert_eq!(access.check(&listed), Ok(()));
+        assert_eq!(access.check(&other), Err(ShareAccessError::NotAllowed));
+
+        let access = ShareAccess::new(Some([listed].into()), Some("secret"));
+        assert_eq!(
+            access.check(&listed),
+            Err(ShareAccessError::PasswordRequired)
+        );
+        access.inner.authenticated.lock().unwrap().insert(listed);
+        assert_eq!(access.check(&listed), Ok(()));
+        assert_eq!(access.check(&other), Err(ShareAccessError::NotAllowed));
+    }
+
+    #[test]
+    fn denied_receivers_are_refused() {
+        let receiver = iroh::SecretKey::generate().public();
+        let access = ShareAccess::new(None, None);
+        assert_eq!(access.confirmed(&receiver), None);
+
+        access.confirm(receiver, true);
+        assert_eq!(access.check(&receiver), Ok(()));
+        access.confirm(receiver, false);
+        assert_eq!(access.confirmed(&receiver), Some(false));
+        assert_eq!(access.check(&receiver), Err(ShareAccessError::NotAllowed));
+    }
+}
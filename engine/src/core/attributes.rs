@@ -0,0 +1,275 @@
+use iroh_blobs::{api::Store, format::collection::Collection, Hash};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Name of the collection entry holding the [`AttributesManifest`].
+///
+/// The manifest is always the last entry. Senders rename a shared file with this name, so a
+/// last entry with this name is never user data.
+pub const ATTRIBUTES_ENTRY: &str = ".sendme-attributes.json";
+
+/// # Description
+/// File system attributes of the shared entries, which the collection itself does not carry.
+/// Stored as an extra blob in the collection, so it is verified and resumed like file data.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttributesManifest {
+    /// Keyed by entry name.
+    #[serde(default)]
+    pub files: BTreeMap<String, EntryAttributes>,
+    /// Every shared directory, including empty ones, keyed by its name in the collection.
+    #[serde(default)]
+    pub dirs: BTreeMap<String, EntryAttributes>,
+    /// Symlinks of a `SymlinkPolicy::Preserve` share: name to relative target, using `/`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub links: BTreeMap<String, String>,
+}
+
+/// Attributes of one file or directory. Unknown values are left as they are on the receiver.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EntryAttributes {
+    /// Unix permission bits. Not recorded on other platforms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+    /// Modification time in milliseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_ms: Option<u64>,
+}
+
+impl EntryAttributes {
+    /// Reads the attributes of a local file or directory, following symlinks.
+    pub fn read(path: &Path) -> std::io::Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        #[cfg(unix)]
+        let mode = {
+            use std::os::unix::fs::PermissionsExt;
+            Some(metadata.permissions().mode() & 0o777)
+        };
+        #[cfg(not(unix))]
+        let mode = None;
+        let modified_ms = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|since_epoch| since_epoch.as_millis() as u64);
+        Ok(Self { mode, modified_ms })
+    }
+
+    /// Applies the attributes to a local file or directory. The modification time is set first,
+    /// since the mode may take away the permission needed for it.
+    pub fn apply(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(modified_ms) = self.modified_ms {
+            let time = UNIX_EPOCH + std::time::Duration::from_millis(modified_ms);
+            set_modified(path, time)?;
+        }
+        #[cfg(unix)]
+        if let Some(mode) = self.mode {
+            use std::os::unix::fs::PermissionsExt;
+            // Only permission bits; setuid, setgid and sticky from a peer are never applied.
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+        Ok(())
+    }
+}
+
+fn set_modified(path: &Path, time: SystemTime) -> std::io::Result<()> {
+    let file = if path.is_dir() {
+        std::fs::File::open(path)?
+    } else {
+        std::fs::File::options().write(true).open(path)?
+    };
+    file.set_modified(time)
+}
+
+/// Checks that the link `name` with the relative `target` stays inside the shared directory,
+/// the first component of `name`. Returns the target with `.` components removed.
+///
+/// `..` is only accepted at the start of the target: after a component that is itself a link
+/// it would climb from the link target instead, which the name alone cannot tell.
+pub fn link_target_within(name: &str, target: &str) -> Option<String> {
+    // Depth of the directory holding the link, below the shared directory.
+    let mut depth = name.split('/').count().checked_sub(2)?;
+    if target.is_empty() || target.starts_with('/') {
+        return None;
+    }
+    let mut parts = Vec::new();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                if parts.last().is_some_and(|last| *last != "..") {
+                    return None;
+                }
+                depth = depth.checked_sub(1)?;
+                parts.push(part);
+            }
+            _ => {
+                if part.contains('\\') || part.contains(':') || part.contains('\0') {
+                    return None;
+                }
+                depth += 1;
+                parts.push(part);
+            }
+        }
+    }
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+/// Creates the symlink `path` pointing to a target checked with [`link_target_within`].
+fn create_link(path: &Path, target: &str) -> std::io::Result<()> {
+    let target: PathBuf = target.split('/').collect();
+    #[cfg(unix)]
+    return std::os::unix::fs::symlink(&target, path);
+    #[cfg(windows)]
+    {
+        let is_dir = path
+            .parent()
+            .is_some_and(|parent| parent.join(&target).is_dir());
+        if is_dir {
+            return std::os::windows::fs::symlink_dir(&target, path);
+        }
+        return std::os::windows::fs::symlink_file(&target, path);
+    }
+    #[cfg(not(any(unix, windows)))]
+    Err(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "symlinks are not supported on this platform",
+    ))
+}
+
+/// Splits the manifest entry off a received collection. The returned collection holds the
+/// shared files only, at the same positions as in the original.
+pub fn split_manifest(collection: Collection) -> (Collection, Option<Hash>) {
+    let manifest = match collection.iter().last() {
+        Some((name, hash)) if name == ATTRIBUTES_ENTRY => *hash,
+        _ => return (collection, None),
+    };
+    let len = collection.len() - 1;
+    (collection.into_iter().take(len).collect(), Some(manifest))
+}
+
+/// Reads the manifest blob from the store. A manifest that cannot be parsed is ignored.
+pub async fn load_manifest(db: &Store, hash: Hash) -> anyhow::Result<Option<AttributesManifest>> {
+    let bytes = db.blobs().get_bytes(hash).await?;
+    match serde_json::from_slice(&bytes) {
+        Ok(manifest) => Ok(Some(manifest)),
+        Err(e) => {
+            tracing::warn!("ignoring unreadable attributes manifest: {}", e);
+            Ok(None)
+        }
+    }
+}
+
+/// Applies the manifest to the exported files, given as (entry name, path) pairs. With
+/// `output_dir` set the shared directories and symlinks are created below it, and directories
+/// get their attributes too. Existing files are never replaced by a link.
+///
+/// Failures are logged and skipped: file systems like FAT cannot hold every attribute, and
+/// that must not fail a receive whose data arrived fine.
+pub async fn apply_manifest(
+    manifest: AttributesManifest,
+    files: Vec<(String, PathBuf)>,
+    output_dir: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    tokio::task::spawn_blocking(move || {
+        for (name, path) in files {
+            if let Some(attributes) = manifest.files.get(&name) {
+                if let Err(e) = attributes.apply(&path) {
+                    tracing::warn!("failed to apply attributes to {}: {}", name, e);
+                }
+            }
+        }
+        let Some(output_dir) = output_dir else {
+            return Ok(());
+        };
+        let mut dirs = Vec::with_capacity(manifest.dirs.len());
+        for (name, attributes) in &manifest.dirs {
+            let path = super::receive::get_export_path(&output_dir, name)?;
+            match std::fs::create_dir_all(&path) {
+                Ok(()) => dirs.push((name, path, attributes)),
+                Err(e) => tracing::warn!("failed to create {}: {}", name, e),
+            }
+        }
+        for (name, target) in &manifest.links {
+            let path = super::receive::get_export_path(&output_dir, name)?;
+            let Some(target) = link_target_within(name, target) else {
+                tracing::warn!("not creating link {} pointing outside of the share", name);
+                continue;
+            };
+            if path.symlink_metadata().is_ok() {
+                tracing::warn!("not creating link {}, the path already exists", name);
+                continue;
+            }
+            // A link inside a linked directory would be resolved from the other directory.
+            let below_link = path
+                .ancestors()
+                .skip(1)
+                .take_while(|ancestor| *ancestor != output_dir)
+                .any(|ancestor| ancestor.is_symlink());
+            if below_link {
+                tracing::warn!("not creating link {} below another link", name);
+                continue;
+            }
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            if let Err(e) = create_link(&path, &target) {
+                tracing::warn!("failed to create link {}: {}", name, e);
+            }
+        }
+        // Deepest first, so that setting a directory's attributes does not touch its parent's
+        // modification time after it was set.
+        for (name, path, attributes) in dirs.iter().rev() {
+            if let Err(e) = attributes.apply(path) {
+                tracing::warn!("failed to apply attributes to {}: {}", name, e);
+            }
+        }
+        anyhow::Ok(())
+    })
+    .await?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn manifest_is_split_off_only_as_last_entry() {
+        let a = Hash::new(b"a");
+        let m = Hash::new(b"manifest");
+        let collection: Collection = [("a".to_string(), a), (ATTRIBUTES_ENTRY.to_string(), m)]
+            .into_iter()
+            .collect();
+        let (files, manifest) = split_manifest(collection);
+        assert_eq!(manifest, Some(m));
+        assert_eq!(files.len(), 1);
+
+        let collection: Collection = [(ATTRIBUTES_ENTRY.to_string(), m), ("z".to_string(), a)]
+            .into_iter()
+            .collect();
+        let (files, manifest) = split_manifest(collection);
+        assert_eq!(manifest, None);
+        assert_eq!(files.len(), 2);
+    }
+
+    #[test]
+    fn link_targets_must_stay_inside_the_share() {
+        assert_eq!(
+            link_target_within("share/a/link", "../b.txt").as_deref(),
+            Some("../b.txt")
+        );
+        assert_eq!(
+            link_target_within("share/link", "./sub/./c").as_deref(),
+            Some("sub/c")
+        );
+        assert_eq!(link_target_within("share/link", "../outside"), None);
+        assert_eq!(link_target_within("share/a/link", "../../x"), None);
+        assert_eq!(link_target_within("share/a/link", "sub/../../x"), None);
+        assert_eq!(link_target_within("share/link", "/etc/passwd"), None);
+        assert_eq!(link_target_within("share/link", "C:\\x"), None);
+        assert_eq!(link_target_within("link", "target"), None);
+    }
+}
@@ -0,0 +1,161 @@
+use anyhow::Context;
+use iroh::EndpointId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// # Description
+/// A known peer in the address book.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Contact {
+    pub endpoint_id: String,
+    pub name: String,
+    /// Unix timestamp (seconds) when the contact was added or last renamed.
+    pub added_at: u64,
+}
+
+/// # Description
+/// Maps endpoint IDs of trusted peers to short human names, so a receive can report who it
+/// is from. Only meaningful for senders with a persistent identity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AddressBook {
+    #[serde(skip)]
+    path: PathBuf,
+    contacts: BTreeMap<String, Contact>,
+}
+
+impl AddressBook {
+    /// Default location for callers without an app data directory of their own.
+    pub fn default_path() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("sendme")
+            .join("contacts.json")
+    }
+
+    /// Loads the address book stored at `path`. A missing file is an empty address book.
+    pub fn load(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let mut book: AddressBook = match std::fs::read(&path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .with_context(|| format!("invalid address book {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => AddressBook::default(),
+            Err(e) => return Err(e).context("failed to read address book"),
+        };
+        book.path = path;
+        Ok(book)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adds a peer or renames it if it is already known, then saves.
+    pub fn add(&mut self, endpoint_id: &str, name: &str) -> anyhow::Result<Contact> {
+        let endpoint_id = EndpointId::from_str(endpoint_id.trim())
+            .map_err(|e| anyhow::anyhow!("invalid endpoint ID: {e}"))?
+            .to_string();
+        let name = name.trim();
+        anyhow::ensure!(!name.is_empty(), "contact name must not be empty");
+
+        let added_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let contact = Contact {
+            endpoint_id: endpoint_id.clone(),
+            name: name.to_string(),
+            added_at,
+        };
+        self.contacts.insert(endpoint_id, contact.clone());
+        self.save()?;
+        Ok(contact)
+    }
+
+    /// Removes a peer and saves. Returns whether it was known.
+    pub fn remove(&mut self, endpoint_id: &str) -> anyhow::Result<bool> {
+        let removed = self.contacts.remove(endpoint_id.trim()).is_some();
+        if removed {
+            self.save()?;
+        }
+        Ok(removed)
+    }
+
+    /// Name of the peer with this endpoint ID, if it is in the address book.
+    pub fn name_for(&self, endpoint_id: &EndpointId) -> Option<&str> {
+        self.contacts
+            .get(&endpoint_id.to_string())
+            .map(|c| c.name.as_str())
+    }
+
+    /// Endpoint ID of the peer with this name, ignoring case.
+    pub fn endpoint_id_for(&self, name: &str) -> Option<EndpointId> {
+        let name = name.trim().to_lowercase();
+        self.contacts
+            .values()
+            .find(|c| c.name.to_lowercase() == name)
+            .and_then(|c| EndpointId::from_str(&c.endpoint_id).ok())
+    }
+
+    /// All contacts, ordered by name.
+    pub fn list(&self) -> Vec<Contact> {
+        let mut contacts: Vec<Contact> = self.contacts.values().cloned().collect();
+        contacts.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        contacts
+    }
+
+    fn save(&self) -> anyhow::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let bytes = serde_json::to_vec_pretty(self)?;
+        let tmp_path = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp_path, bytes)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contacts_roundtrip_through_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("contacts.json");
+        let peer = iroh::SecretKey::generate().public();
+
+        let mut book = AddressBook::load(&path).unwrap();
+        assert!(book.list().is_empty());
+        book.add(&peer.to_string(), "Alice's laptop").unwrap();
+
+        let reloaded = AddressBook::load(&path).unwrap();
+        assert_eq!(reloaded.name_for(&peer), Some("Alice's laptop"));
+        assert_eq!(reloaded.endpoint_id_for("alice's LAPTOP"), Some(peer));
+        let unknown = iroh::SecretKey::generate().public();
+        assert_eq!(reloaded.name_for(&unknown), None);
+    }
+
+    #[test]
+    fn rejects_invalid_entries_and_removes() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut book = AddressBook::load(dir.path().join("contacts.json")).unwrap();
+        let peer = iroh::SecretKey::generate().public().to_string();
+
+        assert!(book.add("not-an-id", "Bob").is_err());
+        assert!(book.add(&peer, "  ").is_err());
+
+        book.add(&peer, "Bob").unwrap();
+        book.add(&peer, "Bob's desktop").unwrap();
+        assert_eq!(book.list().len(), 1);
+        assert_eq!(book.list()[0].name, "Bob's desktop");
+
+        assert!(book.remove(&peer).unwrap());
+        assert!(!book.remove(&peer).unwrap());
+        assert!(AddressBook::load(book.path()).unwrap().list().is_empty());
+    }
+}
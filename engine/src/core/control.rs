@@ -0,0 +1,95 @@
+use std::sync::{Arc, Mutex};
+use tokio::sync::watch;
+
+/// # Description
+/// Pauses, resumes and paces a running receive from outside, like `ReceiveOptions::cancel_token`
+/// cancels it. While paused the receive stops reading from the sender but keeps its connection
+/// and the data it already has, and continues with the missing ranges once resumed.
+/// Clones control the same receive.
+#[derive(Debug, Clone)]
+pub struct ReceiveControl {
+    paused: Arc<watch::Sender<bool>>,
+    rate_limit: Arc<Mutex<Option<u64>>>,
+}
+
+impl ReceiveControl {
+    pub fn new() -> Self {
+        Self {
+            paused: Arc::new(watch::Sender::new(false)),
+            rate_limit: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Pauses the receive. Returns false if it was already paused.
+    pub fn pause(&self) -> bool {
+        self.paused
+            .send_if_modified(|paused| !std::mem::replace(paused, true))
+    }
+
+    /// Resumes a paused receive. Returns false if it was not paused.
+    pub fn resume(&self) -> bool {
+        self.paused
+            .send_if_modified(|paused| std::mem::replace(paused, false))
+    }
+
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Caps the download rate in bytes per second, `None` or zero for no limit.
+    pub fn set_rate_limit(&self, bytes_per_sec: Option<u64>) {
+        *self.rate_limit.lock().unwrap() = bytes_per_sec.filter(|rate| *rate > 0);
+    }
+
+    pub fn rate_limit(&self) -> Option<u64> {
+        *self.rate_limit.lock().unwrap()
+    }
+
+    /// Waits until the receive is paused.
+    pub(crate) async fn paused(&self) {
+        let mut paused = self.paused.subscribe();
+        // The sender lives as long as `self`, so this cannot fail.
+        let _ = paused.wait_for(|paused| *paused).await;
+    }
+
+    /// Waits until the receive is resumed.
+    pub(crate) async fn resumed(&self) {
+        let mut paused = self.paused.subscribe();
+        let _ = paused.wait_for(|paused| !*paused).await;
+    }
+}
+
+impl Default for ReceiveControl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn pause_and_resume_are_shared_by_clones() {
+        let control = ReceiveControl::new();
+        let remote = control.clone();
+
+        assert!(remote.pause());
+        assert!(!remote.pause());
+        assert!(control.is_paused());
+        control.paused().await;
+
+        let waiting = tokio::spawn({
+            let control = control.clone();
+            async move { control.resumed().await }
+        });
+        assert!(remote.resume());
+        assert!(!remote.resume());
+        waiting.await.unwrap();
+
+        remote.set_rate_limit(Some(0));
+        assert_eq!(control.rate_limit(), None);
+        remote.set_rate_limit(Some(1024));
+        assert_eq!(control.rate_limit(), Some(1024));
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::sync::oneshot;
+
+/// # Description
+/// Questions the engine is waiting on the app to answer, e.g. what to do with an existing file.
+///
+/// The engine registers a question with [`PendingDecisions::request`], emits an event carrying
+/// the returned ID and awaits the [`PendingDecision`]. The app answers with [`PendingDecisions::resolve`].
+/// Clones share the same set of questions, so one instance can be kept in app state and handed
+/// to every transfer.
+pub struct PendingDecisions<T> {
+    next_id: Arc<AtomicU64>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<T>>>>,
+}
+
+impl<T> PendingDecisions<T> {
+    pub fn new() -> Self {
+        Self {
+            next_id: Arc::new(AtomicU64::new(1)),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Registers a new question. The returned future errors if the question is forgotten, and
+    /// forgets the question itself when it is dropped unanswered.
+    pub fn request(&self) -> (u64, PendingDecision<T>) {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().unwrap().insert(id, tx);
+        let decision = PendingDecision {
+            id,
+            rx,
+            pending: self.pending.clone(),
+        };
+        (id, decision)
+    }
+
+    /// Answers the question with this ID. Returns false if it is unknown or no longer awaited.
+    pub fn resolve(&self, id: u64, decision: T) -> bool {
+        match self.pending.lock().unwrap().remove(&id) {
+            Some(tx) => tx.send(decision).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Drops a question that is no longer awaited, e.g. after a timeout.
+    pub fn forget(&self, id: u64) {
+        self.pending.lock().unwrap().remove(&id);
+    }
+}
+
+/// # Description
+/// The answer to one question of [`PendingDecisions`]. Dropping it, e.g. when the transfer is
+/// cancelled or the wait times out, removes the question so it cannot pile up.
+pub struct PendingDecision<T> {
+    id: u64,
+    rx: oneshot::Receiver<T>,
+    pending: Arc<Mutex<HashMap<u64, oneshot::Sender<T>>>>,
+}
+
+impl<T> Future for PendingDecision<T> {
+    type Output = Result<T, oneshot::error::RecvError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.rx).poll(cx)
+    }
+}
+
+impl<T> Drop for PendingDecision<T> {
+    fn drop(&mut self) {
+        self.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl<T> Clone for PendingDecisions<T> {
+    fn clone(&self) -> Self {
+        Self {
+            next_id: self.next_id.clone(),
+            pending: self.pending.clone(),
+        }
+    }
+}
+
+impl<T> Default for PendingDecisions<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> fmt::Debug for PendingDecisions<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PendingDecisions")
+            .field("pending", &self.pending.lock().unwrap().len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn decisions_are_routed_by_id() {
+        let decisions = PendingDecisions::<&str>::new();
+        let (first, first_rx) = decisions.request();
+        let (second, second_rx) = decisions.clone().request();
+        assert_ne!(first, second);
+
+        assert!(decisions.resolve(second, "b"));
+        assert!(decisions.resolve(first, "a"));
+        assert!(!decisions.resolve(first, "again"));
+        assert_eq!(first_rx.await.unwrap(), "a");
+        assert_eq!(second_rx.await.unwrap(), "b");
+
+        let (third, third_rx) = decisions.request();
+        decisions.forget(third);
+        assert!(third_rx.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn dropped_decisions_are_forgotten() {
+        let decisions = PendingDecisions::<&str>::new();
+        let (id, decision) = decisions.request();
+        drop(decision);
+        assert!(!decisions.resolve(id, "late"));
+        assert!(decisions.pending.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+
+use super::receivers::ReceiverProgress;
+use super::types::{
+    ExportConflict, FileMetadata, ShareAccessError, ShareExpiryReason, SkippedEntry,
+};
+
+/// # Description
+/// Everything the engine reports while a share or receive is running.
+///
+/// Serialized with an `event` tag holding the same kebab-case name the frontend has always
+/// listened on, e.g. `{"event":"receive-progress","bytesTransferred":10,...}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "event",
+    rename_all = "kebab-case",
+    rename_all_fields = "camelCase"
+)]
+pub enum TransferEvent {
+    // Send side
+    TransferStarted,
+    TransferProgress {
+        bytes_transferred: u64,
+        total_bytes: u64,
+        speed_bps: f64,
+    },
+    TransferCompleted,
+    TransferFailed,
+    ActiveConnectionCount {
+        count: usize,
+    },
+    /// Files and directories that could not be shared, emitted before the share starts and
+    /// with every update of a live share.
+    ShareWarnings {
+        skipped: Vec<SkippedEntry>,
+    },
+    /// The share reached its time or download limit and was stopped.
+    ShareExpired {
+        reason: ShareExpiryReason,
+    },
+    /// A receiver was refused, see `SendOptions::allowed_receivers` and `SendOptions::password`.
+    ConnectionRejected {
+        endpoint_id: String,
+        reason: ShareAccessError,
+    },
+    /// A new receiver wants to fetch the share and `SendOptions::confirm_receivers` is set. It
+    /// is served once the app accepts `id` through `SendOptions::connection_decisions`.
+    ConnectionRequest {
+        id: u64,
+        endpoint_id: String,
+        contact_name: Option<String>,
+    },
+    /// Progress of one receiver, see `SendResult::receivers`.
+    ReceiverProgress(ReceiverProgress),
+    /// A live share published a new version.
+    ShareUpdated {
+        hash: String,
+        version: u64,
+        size: u64,
+        file_count: u64,
+    },
+
+    // Receive side
+    ReceiveStarted,
+    ReceiveProgress {
+        bytes_transferred: u64,
+        total_bytes: u64,
+        speed_bps: f64,
+    },
+    ReceiveCompleted,
+    ReceiveCancelled,
+    /// The download was paused through `ReceiveOptions::control`.
+    ReceivePaused,
+    ReceiveResumed,
+    /// Address book name of the sender, emitted before connecting.
+    ReceiveSender {
+        name: String,
+    },
+    ReceiveFileMetadata {
+        metadata: FileMetadata,
+    },
+    /// Names of the collection entries, emitted before export.
+    ReceiveFileNames {
+        names: Vec<String>,
+    },
+    ReceiveConflicts {
+        conflicts: Vec<ExportConflict>,
+    },
+    /// An export target already exists and `ConflictPolicy::Ask` is set. The export waits until
+    /// the app resolves `id` through `ReceiveOptions::conflict_decisions`.
+    ExportConflict {
+        id: u64,
+        name: String,
+        path: String,
+        existing_size: u64,
+        incoming_size: u64,
+    },
+    ExportStarted {
+        total_files: usize,
+    },
+    /// Emitted while a file is copied out of the store.
+    ExportProgress {
+        file_name: String,
+        file_index: usize,
+        total_files: usize,
+        bytes_copied: u64,
+        total_bytes: u64,
+    },
+    ExportCompleted,
+}
+
+impl TransferEvent {
+    /// The event name, matching the serialized `event` tag.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TransferStarted => "transfer-started",
+            Self::TransferProgress { .. } => "transfer-progress",
+            Self::TransferCompleted => "transfer-completed",
+            Self::TransferFailed => "transfer-failed",
+            Self::ActiveConnectionCount { .. } => "active-connection-count",
+            Self::ShareWarnings { .. } => "share-warnings",
+            Self::ConnectionRejected { .. } => "connection-rejected",
+            Self::ConnectionRequest { .. } => "connection-request",
+            Self::ReceiverProgress(_) => "receiver-progress",
+            Self::ShareUpdated { .. } => "share-updated",
+            Self::ShareExpired { .. } => "share-expired",
+            Self::ReceiveStarted => "receive-started",
+            Self::ReceiveProgress { .. } => "receive-progress",
+            Self::ReceiveCompleted => "receive-completed",
+            Self::ReceiveCancelled => "receive-cancelled",
+            Self::ReceivePaused => "receive-paused",
+            Self::ReceiveResumed => "receive-resumed",
+            Self::ReceiveSender { .. } => "receive-sender",
+            Self::ReceiveFileMetadata { .. } => "receive-file-metadata",
+            Self::ReceiveFileNames { .. } => "receive-file-names",
+            Self::ReceiveConflicts { .. } => "receive-conflicts",
+            Self::ExportConflict { .. } => "export-conflict",
+            Self::ExportStarted { .. } => "export-started",
+            Self::ExportProgress { .. } => "export-progress",
+            Self::ExportCompleted => "export-completed",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::receivers::ReceiverStatus;
+
+    #[test]
+    fn serialized_tag_matches_name() {
+        let events = [
+            TransferEvent::TransferStarted,
+            TransferEvent::ActiveConnectionCount { count: 2 },
+            TransferEvent::ShareUpdated {
+                hash: "ab".into(),
+                version: 1,
+                size: 3,
+                file_count: 1,
+            },
+            TransferEvent::ConnectionRejected {
+                endpoint_id: "ab".into(),
+                reason: ShareAccessError::NotAllowed,
+            },
+            TransferEvent::ConnectionRequest {
+                id: 1,
+                endpoint_id: "ab".into(),
+                contact_name: Some("Alice".into()),
+            },
+            TransferEvent::ReceiverProgress(ReceiverProgress {
+                connection_id: 3,
+                endpoint_id: "ab".into(),
+                contact_name: None,
+                bytes_sent: 1,
+                total_bytes: 2,
+                speed_bps: 0.5,
+                status: ReceiverStatus::Active,
+            }),
+            TransferEvent::ReceiveFileNames {
+                names: vec!["a.txt".into()],
+            },
+            TransferEvent::ExportProgress {
+                file_name: "a.txt".into(),
+                file_index: 0,
+                total_files: 1,
+                bytes_copied: 1,
+                total_bytes: 2,
+            },
+        ];
+        for event in events {
+            let json = serde_json::to_value(&event).unwrap();
+            assert_eq!(json["event"], event.name());
+            let back: TransferEvent = serde_json::from_value(json.clone()).unwrap();
+            assert_eq!(serde_json::to_value(&back).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn progress_fields_are_camel_case() {
+        let json = serde_json::to_value(TransferEvent::ReceiveProgress {
+            bytes_transferred: 5,
+            total_bytes: 10,
+            speed_bps: 1.5,
+        })
+        .unwrap();
+        assert_eq!(json["event"], "receive-progress");
+        assert_eq!(json["bytesTransferred"], 5);
+        assert_eq!(json["totalBytes"], 10);
+        assert_eq!(json["speedBps"], 1.5);
+    }
+}
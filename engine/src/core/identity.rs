@@ -0,0 +1,183 @@
+use anyhow::Context;
+use iroh::{EndpointId, SecretKey};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// # Description
+/// The persistent endpoint key of this installation, so that receivers can recognise a sender
+/// and `AddrInfoOptions::Id` tickets stay the same across shares.
+///
+/// Only one endpoint may use the key at a time: two endpoints with the same ID would fight over
+/// the home relay and the published address. Callers take a lease with [`Identity::try_lease`] and
+/// fall back to an ephemeral key when it is already taken.
+#[derive(Debug)]
+pub struct Identity {
+    path: PathBuf,
+    secret_key: SecretKey,
+    leased: Arc<AtomicBool>,
+}
+
+/// Holds the identity key for one endpoint. Dropping it releases the identity again.
+#[derive(Debug)]
+pub struct IdentityLease {
+    secret_key: SecretKey,
+    leased: Arc<AtomicBool>,
+}
+
+impl IdentityLease {
+    pub fn secret_key(&self) -> &SecretKey {
+        &self.secret_key
+    }
+}
+
+impl Drop for IdentityLease {
+    fn drop(&mut self) {
+        self.leased.store(false, Ordering::Release);
+    }
+}
+
+impl Identity {
+    /// Default key location for callers without an app data directory of their own.
+    pub fn default_path() -> PathBuf {
+        dirs::data_local_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("sendme")
+            .join("identity.key")
+    }
+
+    /// Loads the key stored at `path`, creating and persisting a new one on first run.
+    pub fn load_or_create(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let secret_key = match std::fs::read_to_string(&path) {
+            Ok(contents) => parse_key(&contents)
+                .with_context(|| format!("invalid identity key in {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let key = SecretKey::generate();
+                write_key(&path, &key)?;
+                tracing::info!(endpoint_id = %key.public(), "created new identity");
+                key
+            }
+            Err(e) => return Err(e).context("failed to read identity key"),
+        };
+        Ok(Self {
+            path,
+            secret_key,
+            leased: Arc::new(AtomicBool::new(false)),
+        })
+    }
+
+    pub fn endpoint_id(&self) -> EndpointId {
+        self.secret_key.public()
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Takes the key for a new endpoint, or `None` if another endpoint is already using it.
+    pub fn try_lease(&self) -> Option<IdentityLease> {
+        self.leased
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()?;
+        Some(IdentityLease {
+            secret_key: self.secret_key.clone(),
+            leased: self.leased.clone(),
+        })
+    }
+
+    /// Replaces the key with a freshly generated one. Tickets handed out before keep pointing
+    /// at the old endpoint ID, so this is refused while an endpoint still holds a lease.
+    pub fn rotate(&mut self) -> anyhow::Result<EndpointId> {
+        anyhow::ensure!(
+            !self.leased.load(Ordering::Acquire),
+            "identity is in use by an active transfer"
+        );
+        let key = SecretKey::generate();
+        write_key(&self.path, &key)?;
+        self.secret_key = key;
+        Ok(self.endpoint_id())
+    }
+
+    /// The secret key as hex, in the format accepted by `IROH_SECRET`.
+    pub fn export_secret(&self) -> String {
+        hex::encode(self.secret_key.to_bytes())
+    }
+}
+
+fn parse_key(contents: &str) -> anyhow::Result<SecretKey> {
+    let bytes = hex::decode(contents.trim())?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("expected 32 bytes"))?;
+    Ok(SecretKey::from_bytes(&bytes))
+}
+
+/// Writes the key next to its final location and renames it into place, so a crash never
+/// leaves a truncated key behind. The file is only readable by the current user.
+fn write_key(path: &Path, key: &SecretKey) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let tmp_path = path.with_extension("key.tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options
+        .open(&tmp_path)
+        .with_context(|| format!("failed to write identity key to {}", tmp_path.display()))?;
+    file.write_all(hex::encode(key.to_bytes()).as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn identity_is_persisted_and_reloaded() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("identity.key");
+
+        let first = Identity::load_or_create(&path).unwrap();
+        let second = Identity::load_or_create(&path).unwrap();
+        assert_eq!(first.endpoint_id(), second.endpoint_id());
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+
+    #[test]
+    fn lease_is_exclusive_and_blocks_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut identity = Identity::load_or_create(dir.path().join("identity.key")).unwrap();
+        let old_id = identity.endpoint_id();
+
+        let lease = identity.try_lease().unwrap();
+        assert_eq!(lease.secret_key().public(), old_id);
+        assert!(identity.try_lease().is_none());
+        assert!(identity.rotate().is_err());
+
+        drop(lease);
+        let new_id = identity.rotate().unwrap();
+        assert_ne!(old_id, new_id);
+        let reloaded = Identity::load_or_create(identity.path()).unwrap();
+        assert_eq!(reloaded.endpoint_id(), new_id);
+        assert_eq!(
+            parse_key(&identity.export_secret()).unwrap().public(),
+            new_id
+        );
+    }
+}
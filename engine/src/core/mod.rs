@@ -1,3 +1,13 @@
+pub mod access;
+pub mod attributes;
+pub mod contacts;
+pub mod control;
+pub mod decisions;
+pub mod events;
+pub mod identity;
+pub mod ratelimit;
 pub mod receive;
+pub mod receivers;
+pub mod resume;
 pub mod send;
 pub mod types;
@@ -0,0 +1,179 @@
+use iroh::EndpointId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// # Description
+/// Upload rate limits of a share in bytes per second. `None` or zero means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    /// Limit for all receivers together.
+    pub total: Option<u64>,
+    /// Limit for each receiver endpoint, over all its connections.
+    pub per_receiver: Option<u64>,
+}
+
+impl RateLimits {
+    fn is_limited(&self) -> bool {
+        self.total.is_some_and(|rate| rate > 0) || self.per_receiver.is_some_and(|rate| rate > 0)
+    }
+}
+
+/// # Description
+/// Paces the data a share sends so it stays within its [`RateLimits`]. The provider asks after
+/// every chunk it sent how long to wait before the next one. Clones share their state, so the
+/// limits can be changed through `SendResult::rate_limiter` while the share runs, including for
+/// the connections that are already open.
+#[derive(Debug, Clone, Default)]
+pub struct ShareRateLimiter {
+    inner: Arc<Mutex<LimiterState>>,
+    /// Whether any limit is set, readable without taking the lock for every chunk.
+    limited: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Default)]
+struct LimiterState {
+    limits: RateLimits,
+    /// When the data sent so far is paid off, for all receivers and for each one.
+    total_until: Option<Instant>,
+    receiver_until: HashMap<EndpointId, Instant>,
+}
+
+impl ShareRateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(LimiterState {
+                limits,
+                ..LimiterState::default()
+            })),
+            limited: Arc::new(AtomicBool::new(limits.is_limited())),
+        }
+    }
+
+    pub fn limits(&self) -> RateLimits {
+        self.inner.lock().unwrap().limits
+    }
+
+    /// Applies new limits from the next chunk on. What was sent under the old limits is
+    /// forgiven, so raising a limit takes effect right away.
+    pub fn set_limits(&self, limits: RateLimits) {
+        let mut state = self.inner.lock().unwrap();
+        *state = LimiterState {
+            limits,
+            ..LimiterState::default()
+        };
+        self.limited.store(limits.is_limited(), Ordering::Relaxed);
+    }
+
+    /// Whether any limit is set. Chunks need not be booked otherwise.
+    pub(crate) fn is_limited(&self) -> bool {
+        self.limited.load(Ordering::Relaxed)
+    }
+
+    /// Books `bytes` sent to `receiver` and returns how long to wait before sending more.
+    pub(crate) fn delay(&self, receiver: Option<EndpointId>, bytes: u64) -> Duration {
+        if !self.is_limited() {
+            return Duration::ZERO;
+        }
+        let mut state = self.inner.lock().unwrap();
+        let state = &mut *state;
+        let now = Instant::now();
+        let mut until = now;
+        if let Some(rate) = state.limits.total.filter(|rate| *rate > 0) {
+            let total_until = state.total_until.get_or_insert(now);
+            until = until.max(book(total_until, now, bytes, rate));
+        }
+        if let (Some(rate), Some(receiver)) =
+            (state.limits.per_receiver.filter(|rate| *rate > 0), receiver)
+        {
+            let receiver_until = state.receiver_until.entry(receiver).or_insert(now);
+            until = until.max(book(receiver_until, now, bytes, rate));
+        }
+        until - now
+    }
+
+    /// Forgets the receivers that are paid off, which pace like new ones anyway. Called when a
+    /// connection closes, so that the share does not keep every receiver it ever served.
+    pub(crate) fn prune(&self) {
+        let now = Instant::now();
+        self.inner
+            .lock()
+            .unwrap()
+            .receiver_until
+            .retain(|_, until| *until > now);
+    }
+}
+
+/// Moves the time at which a bucket is paid off forward by the time `bytes` take at `rate`.
+pub(crate) fn book(until: &mut Instant, now: Instant, bytes: u64, rate: u64) -> Instant {
+    *until = (*until).max(now) + Duration::from_secs_f64(bytes as f64 / rate as f64);
+    *until
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MS: Duration = Duration::from_millis(1);
+
+    fn close_to(actual: Duration, expected: Duration) -> bool {
+        actual <= expected && actual + 50 * MS >= expected
+    }
+
+    #[test]
+    fn chunks_are_paced_by_the_tighter_limit() {
+        let alice = iroh::SecretKey::generate().public();
+        let bob = iroh::SecretKey::generate().public();
+        let limiter = ShareRateLimiter::new(RateLimits {
+            total: Some(4000),
+            per_receiver: Some(1000),
+        });
+
+        assert!(close_to(limiter.delay(Some(alice), 500), 500 * MS));
+        assert!(close_to(limiter.delay(Some(alice), 500), 1000 * MS));
+        // Bob has a per receiver budget of its own but shares the total one with Alice.
+        assert!(close_to(limiter.delay(Some(bob), 500), 500 * MS));
+        assert!(close_to(limiter.delay(None, 2000), 875 * MS));
+    }
+
+    #[test]
+    fn changed_limits_apply_right_away() {
+        let alice = iroh::SecretKey::generate().public();
+        let limiter = ShareRateLimiter::new(RateLimits {
+            total: None,
+            per_receiver: Some(10),
+        });
+        assert!(limiter.delay(Some(alice), 100) > Duration::from_secs(9));
+
+        limiter.set_limits(RateLimits::default());
+        assert_eq!(limiter.delay(Some(alice), 100), Duration::ZERO);
+        assert_eq!(limiter.limits(), RateLimits::default());
+        assert!(!limiter.is_limited());
+
+        limiter.set_limits(RateLimits {
+            total: Some(0),
+            per_receiver: None,
+        });
+        assert_eq!(limiter.delay(Some(alice), 100), Duration::ZERO);
+    }
+
+    #[test]
+    fn paid_off_receivers_are_pruned() {
+        let alice = iroh::SecretKey::generate().public();
+        let bob = iroh::SecretKey::generate().public();
+        let limiter = ShareRateLimiter::new(RateLimits {
+            total: None,
+            per_receiver: Some(1000),
+        });
+        limiter.delay(Some(alice), 0);
+        limiter.delay(Some(bob), 10_000);
+
+        limiter.prune();
+        let state = limiter.inner.lock().unwrap();
+        assert_eq!(state.receiver_until.len(), 1);
+        assert!(state.receiver_until.contains_key(&bob));
+    }
+}
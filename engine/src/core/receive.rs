@@ -1,21 +1,37 @@
-use crate::core::send::METADATA_ALPN;
+use crate::core::access::{authenticate, refused_access, MetadataDenied};
+use crate::core::attributes;
+use crate::core::decisions::PendingDecisions;
+use crate::core::events::TransferEvent;
+use crate::core::ratelimit::book;
+use crate::core::resume::{
+    discard_interrupted_download, list_interrupted_downloads, partial_store_dir, read_record,
+    write_record, InterruptedDownload,
+};
+use crate::core::send::{LATEST_ALPN, METADATA_ALPN};
 use crate::core::types::{
-    get_or_create_secret, AppHandle, FileMetadata, ReceiveOptions, ReceiveResult,
+    get_or_create_secret, AppHandle, ConflictAction, ConflictDecision, ConflictPolicy,
+    ConnectionPath, EntrySelector, ExportConflict, FileMetadata, MirrorDiff, ReceiveOptions,
+    ReceiveResult, ReceivedFile, ShareAccessError,
 };
-use iroh::endpoint::presets;
-use iroh::{address_lookup::dns::DnsAddressLookup, Endpoint, TransportAddr};
+use anyhow::Context;
+use globset::{GlobBuilder, GlobSetBuilder};
+use iroh::endpoint::{presets, Connection};
+use iroh::{address_lookup::dns::DnsAddressLookup, Endpoint, EndpointAddr, TransportAddr};
 use iroh_blobs::{
     api::{
-        blobs::{ExportMode, ExportOptions, ExportProgressItem},
+        blobs::{BlobStatus, ExportMode, ExportOptions, ExportProgressItem},
         remote::GetProgressItem,
         Store,
     },
     format::collection::Collection,
     get::{request::get_hash_seq_and_sizes, GetError, Stats},
+    protocol::{ChunkRanges, ChunkRangesSeq, GetRequest},
     store::fs::FsStore,
     ticket::BlobTicket,
+    Hash,
 };
 use n0_future::StreamExt;
+use std::collections::{BTreeSet, HashSet};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Instant;
@@ -24,46 +40,67 @@ use tokio::{
     select,
     time::{timeout, Duration},
 };
+use tokio_util::sync::CancellationToken;
 
-// Helper function to emit events through the app handle
-fn emit_event(app_handle: &AppHandle, event_name: &str) {
+fn emit(app_handle: &AppHandle, event: TransferEvent) {
     if let Some(handle) = app_handle {
-        if let Err(e) = handle.emit_event(event_name) {
-            tracing::warn!("Failed to emit event {}: {}", event_name, e);
+        if let Err(e) = handle.emit(&event) {
+            tracing::warn!("Failed to emit event {}: {}", event.name(), e);
         }
     }
 }
 
-// Helper function to emit progress events with payload
 fn emit_progress_event(
     app_handle: &AppHandle,
     bytes_transferred: u64,
     total_bytes: u64,
     speed_bps: f64,
 ) {
-    if let Some(handle) = app_handle {
-        let event_name = "receive-progress";
-
-        // Convert speed to integer (multiply by 1000 to preserve 3 decimal places)
-        let speed_int = (speed_bps * 1000.0) as i64;
-
-        // Create payload data as colon-separated string
-        let payload = format!("{}:{}:{}", bytes_transferred, total_bytes, speed_int);
+    emit(
+        app_handle,
+        TransferEvent::ReceiveProgress {
+            bytes_transferred,
+            total_bytes,
+            speed_bps,
+        },
+    );
+}
 
-        // Emit the event with appropriate payload
-        if let Err(e) = handle.emit_event_with_payload(event_name, &payload) {
-            tracing::warn!("Failed to emit progress event: {}", e);
+/// Connects to the sender on the blobs protocol, proving the passphrase first if there is one.
+async fn connect(
+    endpoint: &Endpoint,
+    addr: &EndpointAddr,
+    password: Option<&str>,
+) -> anyhow::Result<Connection> {
+    if let Some(password) = password {
+        authenticate(endpoint, addr, password).await?;
+    }
+    match endpoint
+        .connect(addr.clone(), iroh_blobs::protocol::ALPN)
+        .await
+    {
+        Ok(conn) => Ok(conn),
+        Err(e) => {
+            tracing::error!("Connection failed: {}", e);
+            tracing::error!("Error details: {:?}", e);
+            tracing::error!("Tried to connect to node: {}", addr.id);
+            tracing::error!("With relay: {:?}", addr.relay_urls().collect::<Vec<_>>());
+            tracing::error!(
+                "With direct addrs: {:?}",
+                addr.ip_addrs().collect::<Vec<_>>()
+            );
+            Err(anyhow::anyhow!("Connection failed: {}", e))
         }
     }
 }
 
-// Helper function to emit events with payload
-fn emit_event_with_payload(app_handle: &AppHandle, event_name: &str, payload: &str) {
-    if let Some(handle) = app_handle {
-        if let Err(e) = handle.emit_event_with_payload(event_name, payload) {
-            tracing::warn!("Failed to emit event {} with payload: {}", event_name, e);
-        }
-    }
+/// Address book name for the sender of a ticket, if the caller passed contacts.
+fn sender_name(options: &ReceiveOptions, endpoint_id: &iroh::EndpointId) -> Option<String> {
+    options
+        .contacts
+        .as_ref()
+        .and_then(|contacts| contacts.name_for(endpoint_id))
+        .map(str::to_string)
 }
 
 /// # Description
@@ -99,19 +136,20 @@ async fn receive_metadata<S: AsyncReadExt + Unpin>(
     tracing::debug!(bytes = meta_buf.len(), "receive_metadata: body received");
 
     // Deserialize the metadata from JSON
-    let metadata: FileMetadata = serde_json::from_slice(&meta_buf)
-        .map_err(|e| anyhow::anyhow!("metadata json decode failed: {e}"))?;
-
-    // Emit event with file metadata
-    if let Some(emitter) = app_handle {
-        if let Ok(payload) = serde_json::to_string(&metadata) {
-            if let Err(e) = emitter.emit_event_with_payload("receive-file-metadata", &payload) {
-                tracing::warn!("Failed to emit file metadata event: {}", e);
-            }
-        } else {
-            tracing::warn!("Failed to serialize file metadata for event payload");
-        }
-    }
+    let metadata: FileMetadata = match serde_json::from_slice(&meta_buf) {
+        Ok(metadata) => metadata,
+        Err(e) => match serde_json::from_slice::<MetadataDenied>(&meta_buf) {
+            Ok(MetadataDenied { denied }) => return Err(denied.into()),
+            Err(_) => anyhow::bail!("metadata json decode failed: {e}"),
+        },
+    };
+
+    emit(
+        app_handle,
+        TransferEvent::ReceiveFileMetadata {
+            metadata: metadata.clone(),
+        },
+    );
 
     Ok(metadata)
 }
@@ -124,8 +162,12 @@ pub async fn download(
     let ticket = BlobTicket::from_str(&ticket_str)?;
 
     let addr = ticket.addr().clone();
+    let sender_name = sender_name(&options, &addr.id);
 
-    let secret_key = get_or_create_secret()?;
+    let secret_key = match options.secret_key.clone() {
+        Some(key) => key,
+        None => get_or_create_secret()?,
+    };
 
     let mut builder = Endpoint::builder(presets::Minimal)
         .alpns(vec![])
@@ -144,78 +186,208 @@ pub async fn download(
 
     let endpoint = builder.bind().await?;
 
-    // Use system temp directory instead of current_dir for GUI app
-    // This avoids polluting user directories and OS manages cleanup automatically
-    let dir_name = format!(".sendme-recv-{}", ticket.hash().to_hex());
-    let temp_base = std::env::temp_dir();
-    let iroh_data_dir = temp_base.join(&dir_name);
+    let password = options.password.clone();
+    let root = if options.latest {
+        resolve_latest(&endpoint, &addr, password.as_deref())
+            .await
+            .context("failed to ask the sender for the latest version")?
+    } else {
+        ticket.hash()
+    };
+
+    // The store is keyed by the collection hash and kept on failure, so that an interrupted
+    // receive only has to fetch `local.missing()` the next time the same ticket is used.
+    let hash_hex = root.to_hex().to_string();
+    if options.latest {
+        discard_older_versions(&ticket_str, &hash_hex).await?;
+    }
+    let (mut record, new_record) = match read_record(&hash_hex).await {
+        Ok(Some(record)) => (record, false),
+        Ok(None) => (
+            InterruptedDownload::new(ticket_str.clone(), hash_hex.clone(), None),
+            true,
+        ),
+        Err(e) => {
+            tracing::warn!("ignoring unreadable resume record: {}", e);
+            (
+                InterruptedDownload::new(ticket_str.clone(), hash_hex.clone(), None),
+                true,
+            )
+        }
+    };
+    record.ticket = ticket_str.clone();
+    if options.output_dir.is_some() {
+        record.output_dir = options.output_dir.clone();
+    }
+    record.selection = options.selection.clone();
+    record.mirror = options.mirror;
+    record.latest = options.latest;
+    // A new record is only written once payload data arrives: until then there is nothing to
+    // resume, and a receive that fails earlier must not be offered for resuming.
+    if !new_record {
+        write_record(&record).await?;
+    }
+
+    if let Some(name) = &sender_name {
+        emit(
+            &app_handle,
+            TransferEvent::ReceiveSender { name: name.clone() },
+        );
+    }
+
+    let iroh_data_dir = partial_store_dir(&hash_hex);
     let db = FsStore::load(&iroh_data_dir).await?;
     let db2 = db.clone();
+    let endpoint2 = endpoint.clone();
+    let cancel_token = options.cancel_token.clone();
+    let app_handle2 = app_handle.clone();
+    let receive_start_time = Instant::now();
 
     let fut = async move {
-        let hash_and_format = ticket.hash_and_format();
-        let local = db.remote().local(hash_and_format).await?;
+        emit(&app_handle, TransferEvent::ReceiveStarted);
 
-        let (stats, total_files, payload_size) = if !local.is_complete() {
-            // Emit receive-started event
-            emit_event(&app_handle, "receive-started");
+        let output_dir = options.output_dir.clone().unwrap_or_else(|| {
+            dirs::download_dir().unwrap_or_else(|| std::env::current_dir().unwrap())
+        });
 
-            let connection = match endpoint
-                .connect(addr.clone(), iroh_blobs::protocol::ALPN)
+        // The collection itself is fetched first: a selection is given by entry names, a mirror
+        // compares entry hashes with local files, and the attributes manifest has to be told
+        // apart from the shared files before the request for the wanted children is built.
+        let mut connection = None;
+        let mut header_bytes_read = 0;
+        let header = GetRequest::new(
+            root,
+            ChunkRangesSeq::from_ranges([ChunkRanges::all(), ChunkRanges::all()]),
+        );
+        let header_local = db.remote().local_for_request(header).await?;
+        if !header_local.is_complete() {
+            let conn = connect(&endpoint, &addr, password.as_deref()).await?;
+            let stats = match db
+                .remote()
+                .execute_get(conn.clone(), header_local.missing())
                 .await
             {
-                Ok(conn) => conn,
-                Err(e) => {
-                    tracing::error!("Connection failed: {}", e);
-                    tracing::error!("Error details: {:?}", e);
-                    tracing::error!("Tried to connect to node: {}", addr.id);
-                    tracing::error!("With relay: {:?}", addr.relay_urls().collect::<Vec<_>>());
-                    tracing::error!(
-                        "With direct addrs: {:?}",
-                        addr.ip_addrs().collect::<Vec<_>>()
-                    );
-                    return Err(anyhow::anyhow!("Connection failed: {}", e));
-                }
+                Ok(stats) => stats,
+                Err(e) => return Err(get_failed(e, &conn, &endpoint, &addr).await),
+            };
+            header_bytes_read = stats.payload_bytes_read;
+            connection = Some(conn);
+        }
+        let (full_collection, manifest_hash) =
+            attributes::split_manifest(Collection::load(root, db.as_ref()).await?);
+
+        let mut mirror_diff = None;
+        let selected: Vec<usize> = if options.selection.is_empty() {
+            (0..full_collection.len()).collect()
+        } else {
+            select_entries(&full_collection, &options.selection)?
+        };
+        let mut indices = selected.clone();
+        if options.mirror.is_some() {
+            let (changed, diff) = compare_local(&full_collection, &selected, &output_dir).await?;
+            indices = changed;
+            mirror_diff = Some(diff);
+        }
+        // The manifest directly follows the shared files.
+        let request = match manifest_hash {
+            Some(_) => selection_request(
+                root,
+                &indices
+                    .iter()
+                    .copied()
+                    .chain([full_collection.len()])
+                    .collect::<Vec<_>>(),
+            ),
+            None => selection_request(root, &indices),
+        };
+        let local = db.remote().local_for_request(request).await?;
+        let was_cached = local.is_complete();
+
+        let (stats, total_files, payload_size, connection_path) = if !local.is_complete() {
+            let connection = match connection {
+                Some(conn) => conn,
+                None => connect(&endpoint, &addr, password.as_deref()).await?,
             };
 
             let sizes_result =
-                get_hash_seq_and_sizes(&connection, &hash_and_format.hash, 1024 * 1024 * 32, None)
-                    .await;
+                get_hash_seq_and_sizes(&connection, &root, 1024 * 1024 * 32, None).await;
 
             let (_hash_seq, sizes) = match sizes_result {
                 Ok((hash_seq, sizes)) => (hash_seq, sizes),
                 Err(e) => {
                     tracing::error!("Failed to get sizes: {:?}", e);
                     tracing::error!("Error type: {}", std::any::type_name_of_val(&e));
-                    return Err(show_get_error(e).into());
+                    return Err(get_failed(e, &connection, &endpoint, &addr).await);
                 }
             };
-            let _total_size = sizes.iter().copied().sum::<u64>();
             // For payload size, we want the actual file data size
             // The sizes array contains: [collection_size, file1_size, file2_size, ...]
             // We skip the first element (collection metadata) but include all file sizes
-            let payload_size = sizes.iter().skip(1).copied().sum::<u64>();
-            let total_files = (sizes.len().saturating_sub(1)) as u64;
+            let payload_size = indices
+                .iter()
+                .map(|i| sizes.get(i + 1).copied().unwrap_or_default())
+                .sum::<u64>();
+            let total_files = indices.len() as u64;
+
+            let mut recorded = !new_record;
+            if record.payload_size != Some(payload_size) {
+                record.payload_size = Some(payload_size);
+                if recorded {
+                    if let Err(e) = write_record(&record).await {
+                        tracing::warn!("failed to update resume record: {}", e);
+                    }
+                }
+            }
 
-            // Emit initial progress event (0%) so frontend can display total size immediately
-            emit_progress_event(&app_handle, 0, payload_size, 0.0);
+            // Progress only counts the bytes fetched by this attempt, data from an earlier one
+            // is already in the store.
+            let local_size = local_payload_bytes(&db, root, &indices).await?;
 
-            let _local_size = local.local_bytes();
+            // Emit initial progress event so frontend can display total size immediately
+            emit_progress_event(&app_handle, local_size.min(payload_size), payload_size, 0.0);
+            let path_connection = connection.clone();
             let get = db.remote().execute_get(connection, local.missing());
             let mut stats = Stats::default();
             let mut stream = get.stream();
             let mut last_log_offset = 0u64;
             let transfer_start_time = Instant::now();
-
-            while let Some(item) = stream.next().await {
+            let mut paused_for = Duration::ZERO;
+            let mut paced_offset = 0u64;
+            let mut paced_until = Instant::now();
+
+            loop {
+                // Not polling the stream holds the request, with the data received so far in
+                // the store and the connection open.
+                let item = select! {
+                    item = stream.next() => item,
+                    _ = options.control.paused() => {
+                        emit(&app_handle, TransferEvent::ReceivePaused);
+                        let paused_at = Instant::now();
+                        options.control.resumed().await;
+                        paused_for += paused_at.elapsed();
+                        emit(&app_handle, TransferEvent::ReceiveResumed);
+                        continue;
+                    }
+                };
+                let Some(item) = item else {
+                    break;
+                };
                 match item {
                     GetProgressItem::Progress(offset) => {
+                        if !recorded && offset > 0 {
+                            write_record(&record).await?;
+                            recorded = true;
+                        }
+
                         // Emit progress events every 1MB
                         if offset - last_log_offset > 1_000_000 {
                             last_log_offset = offset;
 
                             // Calculate speed and emit progress event
-                            let elapsed = transfer_start_time.elapsed().as_secs_f64();
+                            let elapsed = transfer_start_time
+                                .elapsed()
+                                .saturating_sub(paused_for)
+                                .as_secs_f64();
                             let speed_bps = if elapsed > 0.0 {
                                 offset as f64 / elapsed
                             } else {
@@ -224,19 +396,34 @@ pub async fn download(
 
                             emit_progress_event(
                                 &app_handle,
-                                offset.min(payload_size),
+                                (local_size + offset).min(payload_size),
                                 payload_size,
                                 speed_bps,
                             );
                         }
+
+                        if let Some(rate) = options.control.rate_limit() {
+                            let now = Instant::now();
+                            let bytes = offset.saturating_sub(paced_offset);
+                            let until = book(&mut paced_until, now, bytes, rate);
+                            tokio::time::sleep(until - now).await;
+                        }
+                        paced_offset = offset;
                     }
                     GetProgressItem::Done(value) => {
                         stats = value;
+                        // Kept for an export that fails, see the cached branch below.
+                        if !recorded {
+                            write_record(&record).await?;
+                        }
 
                         // Emit final progress event
-                        let elapsed = transfer_start_time.elapsed().as_secs_f64();
+                        let elapsed = transfer_start_time
+                            .elapsed()
+                            .saturating_sub(paused_for)
+                            .as_secs_f64();
                         let speed_bps = if elapsed > 0.0 {
-                            payload_size as f64 / elapsed
+                            stats.total_bytes_read() as f64 / elapsed
                         } else {
                             0.0
                         };
@@ -250,99 +437,245 @@ pub async fn download(
                     }
                 }
             }
-            (stats, total_files, payload_size)
+            stats.payload_bytes_read += header_bytes_read;
+            let connection_path = ConnectionPath::selected(&path_connection);
+            (stats, total_files, payload_size, connection_path)
         } else {
-            let total_files = local.children().unwrap() - 1;
-            let payload_bytes = 0; // todo local.sizes().skip(2).map(Option::unwrap).sum::<u64>();
-
-            // Emit events for already complete data
-            emit_event(&app_handle, "receive-started");
-            emit_event(&app_handle, "receive-completed");
-
-            (Stats::default(), total_files, payload_bytes)
+            // Everything is already in the store, e.g. from an earlier attempt whose export failed.
+            // Sizes come from the local blobs since the sender is never asked.
+            let collection = subset(&full_collection, &indices);
+            let total_files = collection.len() as u64;
+            let payload_size = local_payload_size(&db, &collection).await?;
+            emit_progress_event(&app_handle, payload_size, payload_size, 0.0);
+
+            (
+                Stats::default(),
+                total_files,
+                payload_size,
+                ConnectionPath::Local,
+            )
         };
 
-        let collection = Collection::load(hash_and_format.hash, db.as_ref()).await?;
+        let collection = subset(&full_collection, &indices);
 
         // Extract file names from collection and emit them BEFORE export
         // This allows the UI to show file names during the export phase
-        let mut file_names: Vec<String> = Vec::new();
-        for (name, _hash) in collection.iter() {
-            file_names.push(name.to_string());
+        let names: Vec<String> = collection
+            .iter()
+            .map(|(name, _hash)| name.to_string())
+            .collect();
+        if !names.is_empty() {
+            emit(&app_handle, TransferEvent::ReceiveFileNames { names });
         }
 
-        // Emit file names information
-        if !file_names.is_empty() {
-            let file_names_json =
-                serde_json::to_string(&file_names).unwrap_or_else(|_| "[]".to_string());
-            emit_event_with_payload(&app_handle, "receive-file-names", &file_names_json);
-        }
+        // A mirror only exports entries that differ from the local file, which it replaces.
+        let conflict_policy = match options.mirror {
+            Some(_) => ConflictPolicy::Overwrite,
+            None => options.conflict_policy,
+        };
+        let (conflicts, files) = export(
+            &db,
+            collection,
+            &output_dir,
+            conflict_policy,
+            &options.conflict_decisions,
+            &options.cancel_token,
+            &app_handle,
+        )
+        .await?;
 
-        // Determine output directory
-        let output_dir = options.output_dir.unwrap_or_else(|| {
-            dirs::download_dir().unwrap_or_else(|| std::env::current_dir().unwrap())
-        });
+        if let (Some(mirror), Some(diff)) = (options.mirror, mirror_diff.as_mut()) {
+            if mirror.delete_extraneous {
+                diff.deleted =
+                    delete_extraneous(&full_collection, &selected, &files, &output_dir).await?;
+            }
+        }
 
-        let conflicts = export(&db, collection, &output_dir).await?;
+        if let Some(hash) = manifest_hash {
+            if let Some(manifest) = attributes::load_manifest(&db, hash).await? {
+                let mut exported: Vec<(String, PathBuf)> = files
+                    .iter()
+                    .map(|file| (file.name.clone(), file.path.clone()))
+                    .collect();
+                // Files a mirror left alone may still differ in their attributes.
+                for name in mirror_diff.iter().flat_map(|diff| &diff.unchanged) {
+                    exported.push((name.clone(), get_export_path(&output_dir, name)?));
+                }
+                // Shared directories are only recreated when the whole share was received.
+                let dirs_root = options.selection.is_empty().then(|| output_dir.clone());
+                attributes::apply_manifest(manifest, exported, dirs_root).await?;
+            }
+        }
 
         if !conflicts.is_empty() {
-            let payload = serde_json::to_string(&conflicts).unwrap_or_else(|_| "[]".to_string());
-            emit_event_with_payload(&app_handle, "receive-conflicts", &payload);
+            emit(
+                &app_handle,
+                TransferEvent::ReceiveConflicts {
+                    conflicts: conflicts.clone(),
+                },
+            );
         }
 
         // Explicit call endpoint.close() to gracefully shutdown the connection
         endpoint.close().await;
 
         // Emit completion event AFTER everything is done
-        emit_event(&app_handle, "receive-completed");
+        emit(&app_handle, TransferEvent::ReceiveCompleted);
 
-        anyhow::Ok((
+        anyhow::Ok(ReceiveResult {
+            message: String::new(),
+            file_path: output_dir,
+            sender_name: None,
             total_files,
             payload_size,
-            stats,
-            output_dir,
-            conflicts.len(),
-        ))
+            bytes_fetched: stats.payload_bytes_read,
+            duration_ms: 0,
+            average_speed_bps: if stats.elapsed.as_secs_f64() > 0.0 {
+                stats.payload_bytes_read as f64 / stats.elapsed.as_secs_f64()
+            } else {
+                0.0
+            },
+            conflicts,
+            files,
+            connection_path,
+            was_cached,
+            mirror: mirror_diff,
+        })
     };
 
-    let (total_files, payload_size, _stats, output_dir, conflict_count) = select! {
+    let mut result = select! {
+        // Checked first, so that a cancel seen by `fut` too is still reported as a cancel.
+        biased;
+        _ = cancel_token.cancelled() => {
+            tracing::warn!("Operation cancelled by user");
+            endpoint2.close().await;
+            db2.shutdown().await?;
+            discard_unrecorded(&hash_hex).await?;
+            emit(&app_handle2, TransferEvent::ReceiveCancelled);
+            anyhow::bail!("Operation cancelled");
+        }
         x = fut => match x {
             Ok(x) => x,
             Err(e) => {
                 tracing::error!("Download operation failed: {}", e);
-                // make sure we shutdown the db before exiting
+                // make sure we shutdown the db before exiting, partial data is kept for resume
+                endpoint2.close().await;
                 db2.shutdown().await?;
+                discard_unrecorded(&hash_hex).await?;
+                // Kept typed, so that callers can ask for a passphrase.
+                if e.downcast_ref::<ShareAccessError>().is_some() {
+                    return Err(e);
+                }
                 anyhow::bail!("error: {e}");
             }
         },
-        _ = tokio::signal::ctrl_c() => {
-            tracing::warn!("Operation cancelled by user");
-            db2.shutdown().await?;
-            anyhow::bail!("Operation cancelled");
-        }
     };
 
-    tokio::fs::remove_dir_all(&iroh_data_dir).await?;
+    db2.shutdown().await?;
+    discard_interrupted_download(&hash_hex).await?;
 
-    let message = if conflict_count > 0 {
-        format!(
-            "Downloaded {} files, {} bytes ({} name conflicts auto-resolved)",
-            total_files, payload_size, conflict_count
-        )
-    } else {
-        format!("Downloaded {} files, {} bytes", total_files, payload_size)
+    result.sender_name = sender_name;
+    result.duration_ms = receive_start_time.elapsed().as_millis() as u64;
+    result.message = result.summary();
+
+    Ok(result)
+}
+
+/// # Description
+/// Resumes a receive that was interrupted earlier, e.g. by a crash or by quitting the app.
+/// Only the ranges still missing from the partial store are requested from the sender.
+/// If `options.output_dir`, `options.selection` or `options.mirror` is not set, the ones of the first
+/// attempt are used. A receive of the latest version of a live share continues with the version
+/// the sender has now; the data of an older one is discarded.
+pub async fn resume_download(
+    ticket_str: String,
+    mut options: ReceiveOptions,
+    app_handle: AppHandle,
+) -> anyhow::Result<ReceiveResult> {
+    let ticket = BlobTicket::from_str(&ticket_str)?;
+    let hash_hex = ticket.hash().to_hex().to_string();
+    // A receive of the latest version is stored under the version it started with.
+    let record = match read_record(&hash_hex).await? {
+        Some(record) => record,
+        None => list_interrupted_downloads()
+            .await?
+            .into_iter()
+            .find(|record| record.latest && record.ticket == ticket_str)
+            .context("no interrupted download found for this ticket")?,
     };
 
-    Ok(ReceiveResult {
-        message,
-        file_path: output_dir,
-    })
+    if options.output_dir.is_none() {
+        options.output_dir = record.output_dir;
+    }
+    if options.selection.is_empty() {
+        options.selection = record.selection;
+    }
+    if options.mirror.is_none() {
+        options.mirror = record.mirror;
+    }
+    options.latest |= record.latest;
+
+    download(ticket_str, options, app_handle).await
+}
+
+/// Removes the partial store of a receive that never fetched payload data and so has no
+/// resume record. It holds the collection at most, which is cheap to fetch again.
+async fn discard_unrecorded(hash_hex: &str) -> anyhow::Result<()> {
+    if !matches!(read_record(hash_hex).await, Ok(Some(_))) {
+        discard_interrupted_download(hash_hex).await?;
+    }
+    Ok(())
+}
+
+/// # Description
+/// Removes interrupted receives of earlier versions of the live share behind `ticket`. The
+/// sender only serves its current version, so they could never finish.
+async fn discard_older_versions(ticket: &str, current: &str) -> anyhow::Result<()> {
+    for record in list_interrupted_downloads().await? {
+        if record.latest && record.ticket == ticket && record.hash != current {
+            tracing::info!(hash = %record.hash, "discarding receive of an older version");
+            discard_interrupted_download(&record.hash).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Asks the sender of a live share for the root hash of its current version.
+async fn resolve_latest(
+    endpoint: &Endpoint,
+    addr: &EndpointAddr,
+    password: Option<&str>,
+) -> anyhow::Result<Hash> {
+    if let Some(password) = password {
+        authenticate(endpoint, addr, password).await?;
+    }
+    let connection = timeout(
+        Duration::from_secs(15),
+        endpoint.connect(addr.clone(), LATEST_ALPN),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("latest connect timeout"))??;
+    let (mut send_stream, mut recv_stream) = timeout(Duration::from_secs(20), connection.open_bi())
+        .await
+        .map_err(|_| anyhow::anyhow!("latest open_bi timeout"))??;
+    send_stream.write_all(&[1]).await?;
+    let mut hash = [0u8; 32];
+    let read = timeout(Duration::from_secs(20), recv_stream.read_exact(&mut hash))
+        .await
+        .map_err(|_| anyhow::anyhow!("latest read timeout"))?;
+    if refused_access(&connection) {
+        return Err(refusal_reason(endpoint, addr).await.into());
+    }
+    read?;
+    let _ = send_stream.finish();
+    Ok(Hash::from_bytes(hash))
 }
 
 /// # Description
 /// Fetches metadata for a given ticket without downloading the file data. This is used to display file information (name, size, thumbnail) in the UI before the user decides to download.
 /// # Returns
 /// A `FileMetadata` struct containing the file name, size, and preview metadata (if any).
+/// Fails with `ShareAccessError` for a protected share without, or with a wrong, `options.password`.
 pub async fn fetch_metadata(
     ticket_str: String,
     options: ReceiveOptions,
@@ -350,9 +683,13 @@ pub async fn fetch_metadata(
     // parse ticket and extract address
     let ticket = BlobTicket::from_str(&ticket_str)?;
     let addr = ticket.addr().clone();
+    let sender_name = sender_name(&options, &addr.id);
 
     // Create a temporary endpoint to connect and fetch metadata
-    let secret_key = get_or_create_secret()?;
+    let secret_key = match options.secret_key.clone() {
+        Some(key) => key,
+        None => get_or_create_secret()?,
+    };
 
     let mut builder = Endpoint::builder(presets::N0)
         // METADATA_ALPN only to indicate a metadata fetch
@@ -394,47 +731,11 @@ pub async fn fetch_metadata(
     for (attempt, path, target_addr) in attempt_plan {
         tracing::info!(attempt, path, "fetch_metadata: connecting to sender");
 
-        let result: anyhow::Result<FileMetadata> = async {
-            let connection = timeout(
-                Duration::from_secs(15),
-                endpoint.connect(target_addr, METADATA_ALPN),
-            )
-            .await
-            .map_err(|_| anyhow::anyhow!("metadata connect timeout"))??;
-
-            tracing::debug!(attempt, path, "fetch_metadata: connection established");
-
-            let (mut send_stream, mut recv_stream) =
-                timeout(Duration::from_secs(20), connection.open_bi())
-                    .await
-                    .map_err(|_| anyhow::anyhow!("metadata open_bi timeout"))??;
-
-            tracing::debug!(attempt, path, "fetch_metadata: bi stream opened");
-
-            // Send 1 byte as a marker to indicate metadata request
-            timeout(Duration::from_secs(10), send_stream.write_all(&[1]))
-                .await
-                .map_err(|_| anyhow::anyhow!("metadata request write timeout"))??;
-
-            tracing::debug!(attempt, path, "fetch_metadata: request marker sent");
-
-            let metadata = timeout(
-                Duration::from_secs(20),
-                receive_metadata(&mut recv_stream, &None),
-            )
-            .await
-            .map_err(|_| anyhow::anyhow!("metadata read timeout"))??;
-
-            // Finish send_stream only AFTER receiving the metadata.
-            // signals the server that we are safely done and it can drop the connection.
-            let _ = send_stream.finish();
-
-            Ok(metadata)
-        }
-        .await;
+        let result = request_metadata(&endpoint, target_addr, options.password.as_deref()).await;
 
         match result {
-            Ok(metadata) => {
+            Ok(mut metadata) => {
+                metadata.sender_name = sender_name;
                 tracing::info!(
                     attempt,
                     path,
@@ -446,6 +747,12 @@ pub async fn fetch_metadata(
                 endpoint.close().await;
                 return Ok(metadata);
             }
+            // Asking again does not change the answer.
+            Err(err) if err.downcast_ref::<ShareAccessError>().is_some() => {
+                tracing::info!(attempt, path, error = %err, "fetch_metadata: access denied");
+                endpoint.close().await;
+                return Err(err);
+            }
             Err(err) => {
                 let will_retry = attempt < 3;
                 tracing::debug!(
@@ -486,28 +793,392 @@ pub async fn fetch_metadata(
     Err(last_error.unwrap_or_else(|| anyhow::anyhow!("metadata fetch failed")))
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
-#[serde(rename_all = "camelCase")]
-struct ExportConflict {
-    original: String,
-    resolved: String,
+/// One metadata request to the sender at `addr`, proving the passphrase first if there is one.
+async fn request_metadata(
+    endpoint: &Endpoint,
+    addr: EndpointAddr,
+    password: Option<&str>,
+) -> anyhow::Result<FileMetadata> {
+    if let Some(password) = password {
+        authenticate(endpoint, &addr, password).await?;
+    }
+    let connection = timeout(
+        Duration::from_secs(15),
+        endpoint.connect(addr, METADATA_ALPN),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("metadata connect timeout"))??;
+
+    tracing::debug!("fetch_metadata: connection established");
+
+    let (mut send_stream, mut recv_stream) = timeout(Duration::from_secs(20), connection.open_bi())
+        .await
+        .map_err(|_| anyhow::anyhow!("metadata open_bi timeout"))??;
+
+    tracing::debug!("fetch_metadata: bi stream opened");
+
+    // Send 1 byte as a marker to indicate metadata request
+    timeout(Duration::from_secs(10), send_stream.write_all(&[1]))
+        .await
+        .map_err(|_| anyhow::anyhow!("metadata request write timeout"))??;
+
+    tracing::debug!("fetch_metadata: request marker sent");
+
+    let metadata = timeout(
+        Duration::from_secs(20),
+        receive_metadata(&mut recv_stream, &None),
+    )
+    .await
+    .map_err(|_| anyhow::anyhow!("metadata read timeout"))??;
+
+    // Finish send_stream only AFTER receiving the metadata.
+    // signals the server that we are safely done and it can drop the connection.
+    let _ = send_stream.finish();
+
+    Ok(metadata)
+}
+
+/// # Description
+/// Asks the sender why it refused this endpoint. Refused connections all close with the same
+/// code, while the metadata protocol answers with the reason.
+async fn refusal_reason(endpoint: &Endpoint, addr: &EndpointAddr) -> ShareAccessError {
+    match request_metadata(endpoint, addr.clone(), None).await {
+        Err(e) => match e.downcast_ref::<ShareAccessError>() {
+            Some(reason) => *reason,
+            None => ShareAccessError::NotAllowed,
+        },
+        // Access was revoked in between, e.g. by a restarted share.
+        Ok(_) => ShareAccessError::NotAllowed,
+    }
+}
+
+/// Positions of the collection entries matched by any of the selectors, in collection order.
+fn select_entries(
+    collection: &Collection,
+    selection: &[EntrySelector],
+) -> anyhow::Result<Vec<usize>> {
+    let mut globs = GlobSetBuilder::new();
+    for selector in selection {
+        if let EntrySelector::Glob(pattern) = selector {
+            globs.add(
+                GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("invalid glob {pattern:?}"))?,
+            );
+        }
+    }
+    let globs = globs.build()?;
+
+    for selector in selection {
+        if let EntrySelector::Index(index) = selector {
+            anyhow::ensure!(
+                *index < collection.len(),
+                "entry index {index} is out of range, the share has {} entries",
+                collection.len()
+            );
+        }
+    }
+
+    let indices: Vec<usize> = collection
+        .iter()
+        .enumerate()
+        .filter(|(index, (name, _))| {
+            globs.is_match(name)
+                || selection.iter().any(|selector| match selector {
+                    EntrySelector::Index(i) => i == index,
+                    // A directory name selects everything below it.
+                    EntrySelector::Name(n) => {
+                        let n = n.trim_end_matches('/');
+                        name == n || name.strip_prefix(n).is_some_and(|r| r.starts_with('/'))
+                    }
+                    EntrySelector::Glob(_) => false,
+                })
+        })
+        .map(|(index, _)| index)
+        .collect();
+    anyhow::ensure!(!indices.is_empty(), "selection matches no entries");
+    Ok(indices)
+}
+
+/// Request for the collection itself and the selected children only.
+fn selection_request(root: Hash, indices: &[usize]) -> GetRequest {
+    let last = indices.iter().copied().max().unwrap_or_default();
+    // Offset 0 is the hash seq, 1 the collection metadata, entry `i` is at `i + 2`.
+    let mut ranges = vec![ChunkRanges::empty(); last + 3];
+    ranges[0] = ChunkRanges::all();
+    ranges[1] = ChunkRanges::all();
+    for index in indices {
+        ranges[index + 2] = ChunkRanges::all();
+    }
+    GetRequest::new(root, ChunkRangesSeq::from_ranges(ranges))
+}
+
+/// Bytes of the selected entries already in the store, without the collection itself.
+async fn local_payload_bytes(db: &Store, root: Hash, indices: &[usize]) -> anyhow::Result<u64> {
+    let last = indices.iter().copied().max().unwrap_or_default();
+    let mut ranges = vec![ChunkRanges::empty(); last + 3];
+    for index in indices {
+        ranges[index + 2] = ChunkRanges::all();
+    }
+    let request = GetRequest::new(root, ChunkRangesSeq::from_ranges(ranges));
+    Ok(db.remote().local_for_request(request).await?.local_bytes())
+}
+
+/// The selected entries of a collection.
+fn subset(collection: &Collection, indices: &[usize]) -> Collection {
+    indices
+        .iter()
+        .map(|&index| {
+            let (name, hash) = &collection[index];
+            (name.clone(), *hash)
+        })
+        .collect()
+}
+
+/// Compares the local files of the given entries with the collection. Returns the entries that
+/// have to be fetched, and a diff with everything but `deleted` filled in.
+async fn compare_local(
+    collection: &Collection,
+    indices: &[usize],
+    output_dir: &Path,
+) -> anyhow::Result<(Vec<usize>, MirrorDiff)> {
+    let mut changed = Vec::new();
+    let mut diff = MirrorDiff::default();
+    for &index in indices {
+        let (name, hash) = &collection[index];
+        let target = get_export_path(output_dir, name)?;
+        match tokio::fs::metadata(&target).await {
+            Ok(existing) if existing.is_file() => {
+                if hash_file(&target).await?.as_bytes() == hash.as_bytes() {
+                    diff.unchanged.push(name.clone());
+                    continue;
+                }
+                diff.updated.push(name.clone());
+            }
+            Ok(_) => diff.updated.push(name.clone()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => diff.added.push(name.clone()),
+            Err(e) => return Err(e.into()),
+        }
+        changed.push(index);
+    }
+    Ok((changed, diff))
+}
+
+/// Deletes local files below the top-level directories of the selected entries that are neither
+/// an entry of the collection nor were written by this receive, and directories left empty.
+/// Returns the names of the deleted files relative to `output_dir`.
+async fn delete_extraneous(
+    collection: &Collection,
+    selected: &[usize],
+    files: &[ReceivedFile],
+    output_dir: &Path,
+) -> anyhow::Result<Vec<String>> {
+    let mut keep: HashSet<PathBuf> = files.iter().map(|file| file.path.clone()).collect();
+    for (name, _) in collection.iter() {
+        keep.insert(get_export_path(output_dir, name)?);
+    }
+    let mut roots = BTreeSet::new();
+    for &index in selected {
+        if let Some((root, _)) = collection[index].0.split_once('/') {
+            roots.insert(get_export_path(output_dir, root)?);
+        }
+    }
+
+    let output_dir = output_dir.to_path_buf();
+    tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<String>> {
+        let mut deleted = Vec::new();
+        // Nothing to delete below a directory that is not there.
+        for root in roots.into_iter().filter(|root| root.is_dir()) {
+            // Contents come before their directory, so emptied directories can be removed.
+            for entry in walkdir::WalkDir::new(&root).contents_first(true) {
+                let entry = entry?;
+                let path = entry.path();
+                if entry.file_type().is_dir() {
+                    if path != root {
+                        // Fails for directories that are not empty, which are kept.
+                        let _ = std::fs::remove_dir(path);
+                    }
+                    continue;
+                }
+                if keep.contains(path) {
+                    continue;
+                }
+                std::fs::remove_file(path)
+                    .with_context(|| format!("failed to delete {}", path.display()))?;
+                let name = path
+                    .strip_prefix(&output_dir)?
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy())
+                    .collect::<Vec<_>>()
+                    .join("/");
+                deleted.push(name);
+            }
+        }
+        Ok(deleted)
+    })
+    .await?
+}
+
+/// Sum of the sizes of all collection entries held completely in the local store.
+async fn local_payload_size(db: &Store, collection: &Collection) -> anyhow::Result<u64> {
+    let mut total = 0u64;
+    for (name, hash) in collection.iter() {
+        match db.blobs().status(*hash).await? {
+            BlobStatus::Complete { size } => total += size,
+            _ => anyhow::bail!("{} is not complete in the local store", name),
+        }
+    }
+    Ok(total)
+}
+
+/// Applies the `ConflictPolicy` of one receive to export targets that already exist.
+struct ConflictResolver<'a> {
+    policy: ConflictPolicy,
+    decisions: &'a PendingDecisions<ConflictDecision>,
+    cancel_token: &'a CancellationToken,
+    app_handle: &'a AppHandle,
+}
+
+impl ConflictResolver<'_> {
+    /// Decides what happens to the entry `name` whose export target already exists.
+    async fn resolve(
+        &mut self,
+        db: &Store,
+        name: &str,
+        hash: &Hash,
+        target: &Path,
+    ) -> anyhow::Result<ExportConflict> {
+        let existing = tokio::fs::metadata(target).await?;
+        let policy = match self.policy {
+            ConflictPolicy::Ask => {
+                let incoming_size = match db.blobs().status(*hash).await? {
+                    BlobStatus::Complete { size } => size,
+                    _ => 0,
+                };
+                self.ask(name, target, existing.len(), incoming_size)
+                    .await?
+            }
+            policy => policy,
+        };
+
+        let action = match policy {
+            // Only regular files are replaced or compared; a directory in the way is renamed around.
+            _ if !existing.is_file() => ConflictAction::Renamed,
+            ConflictPolicy::Rename | ConflictPolicy::Ask => ConflictAction::Renamed,
+            ConflictPolicy::Overwrite => ConflictAction::Overwritten,
+            ConflictPolicy::Skip => ConflictAction::Skipped,
+            ConflictPolicy::SkipIfIdentical => {
+                if is_identical(db, hash, target, existing.len()).await? {
+                    ConflictAction::Identical
+                } else {
+                    ConflictAction::Renamed
+                }
+            }
+        };
+        let resolved = match action {
+            ConflictAction::Renamed => resolve_conflict_path(target)?,
+            _ => target.to_path_buf(),
+        };
+        Ok(ExportConflict {
+            original: target.to_string_lossy().to_string(),
+            resolved: resolved.to_string_lossy().to_string(),
+            action,
+        })
+    }
+
+    /// Asks the app through an `export-conflict` event. An unanswered question keeps the
+    /// existing file; a cancelled receive stops waiting and forgets the question.
+    async fn ask(
+        &mut self,
+        name: &str,
+        target: &Path,
+        existing_size: u64,
+        incoming_size: u64,
+    ) -> anyhow::Result<ConflictPolicy> {
+        if self.app_handle.is_none() {
+            tracing::warn!("conflict policy is ask but nobody can answer, renaming instead");
+            return Ok(ConflictPolicy::Rename);
+        }
+        let (id, decision) = self.decisions.request();
+        emit(
+            self.app_handle,
+            TransferEvent::ExportConflict {
+                id,
+                name: name.to_string(),
+                path: target.to_string_lossy().to_string(),
+                existing_size,
+                incoming_size,
+            },
+        );
+        let decision = select! {
+            decision = decision => decision,
+            _ = self.cancel_token.cancelled() => anyhow::bail!("Operation cancelled"),
+        };
+        Ok(match decision {
+            Ok(decision) => {
+                if decision.apply_to_all {
+                    self.policy = decision.policy;
+                }
+                decision.policy
+            }
+            Err(_) => ConflictPolicy::Skip,
+        })
+    }
+}
+
+/// Whether the file at `path` has exactly the content of the blob `hash`.
+async fn is_identical(db: &Store, hash: &Hash, path: &Path, size: u64) -> anyhow::Result<bool> {
+    match db.blobs().status(*hash).await? {
+        BlobStatus::Complete { size: blob_size } if blob_size == size => {}
+        _ => return Ok(false),
+    }
+    Ok(hash_file(path).await?.as_bytes() == hash.as_bytes())
+}
+
+/// BLAKE3 hash of a local file, which equals the blob hash of the same content.
+async fn hash_file(path: &Path) -> anyhow::Result<blake3::Hash> {
+    let path = path.to_path_buf();
+    let file_hash = tokio::task::spawn_blocking(move || -> std::io::Result<blake3::Hash> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update_reader(std::fs::File::open(&path)?)?;
+        Ok(hasher.finalize())
+    })
+    .await??;
+    Ok(file_hash)
 }
 
 async fn export(
     db: &Store,
     collection: Collection,
     output_dir: &Path,
-) -> anyhow::Result<Vec<ExportConflict>> {
+    conflict_policy: ConflictPolicy,
+    conflict_decisions: &PendingDecisions<ConflictDecision>,
+    cancel_token: &CancellationToken,
+    app_handle: &AppHandle,
+) -> anyhow::Result<(Vec<ExportConflict>, Vec<ReceivedFile>)> {
     let mut conflicts = Vec::new();
+    let mut files = Vec::with_capacity(collection.len());
+    let total_files = collection.len();
+    let mut resolver = ConflictResolver {
+        policy: conflict_policy,
+        decisions: conflict_decisions,
+        cancel_token,
+        app_handle,
+    };
+
+    emit(app_handle, TransferEvent::ExportStarted { total_files });
 
-    for (_i, (name, hash)) in collection.iter().enumerate() {
+    for (file_index, (name, hash)) in collection.iter().enumerate() {
         let desired_target = get_export_path(output_dir, name)?;
         let target = if desired_target.exists() {
-            let resolved = resolve_conflict_path(&desired_target)?;
-            conflicts.push(ExportConflict {
-                original: desired_target.to_string_lossy().to_string(),
-                resolved: resolved.to_string_lossy().to_string(),
-            });
+            let conflict = resolver.resolve(db, name, hash, &desired_target).await?;
+            let action = conflict.action;
+            let resolved = PathBuf::from(&conflict.resolved);
+            conflicts.push(conflict);
+            if matches!(action, ConflictAction::Skipped | ConflictAction::Identical) {
+                continue;
+            }
             resolved
         } else {
             desired_target
@@ -522,31 +1193,58 @@ async fn export(
         let mut stream = db
             .export_with_opts(ExportOptions {
                 hash: *hash,
-                target,
+                target: target.clone(),
                 mode: ExportMode::Copy,
             })
             .stream()
             .await;
 
+        let mut total_bytes = 0u64;
+        let mut last_emitted_offset = 0u64;
+        let emit_export_progress = |bytes_copied: u64, total_bytes: u64| {
+            emit(
+                app_handle,
+                TransferEvent::ExportProgress {
+                    file_name: name.to_string(),
+                    file_index,
+                    total_files,
+                    bytes_copied,
+                    total_bytes,
+                },
+            )
+        };
         while let Some(item) = stream.next().await {
             match item {
-                ExportProgressItem::Size(_size) => {
-                    // Skip progress updates for library version
+                ExportProgressItem::Size(size) => {
+                    total_bytes = size;
+                    emit_export_progress(0, total_bytes);
                 }
-                ExportProgressItem::CopyProgress(_offset) => {
-                    // Skip progress updates for library version
+                ExportProgressItem::CopyProgress(offset) => {
+                    // Emit progress events every 1MB, like the download loop
+                    if offset - last_emitted_offset > 1_000_000 {
+                        last_emitted_offset = offset;
+                        emit_export_progress(offset.min(total_bytes), total_bytes);
+                    }
                 }
                 ExportProgressItem::Done => {
-                    // Export completed
+                    emit_export_progress(total_bytes, total_bytes);
                 }
                 ExportProgressItem::Error(cause) => {
                     anyhow::bail!("error exporting {}: {}", name, cause);
                 }
             }
         }
+
+        files.push(ReceivedFile {
+            name: name.to_string(),
+            path: target,
+            size: total_bytes,
+        });
     }
 
-    Ok(conflicts)
+    emit(app_handle, TransferEvent::ExportCompleted);
+
+    Ok((conflicts, files))
 }
 
 fn resolve_conflict_path(path: &Path) -> anyhow::Result<PathBuf> {
@@ -581,7 +1279,7 @@ fn resolve_conflict_path(path: &Path) -> anyhow::Result<PathBuf> {
     anyhow::bail!("too many filename conflicts for {}", path.display())
 }
 
-fn get_export_path(root: &Path, name: &str) -> anyhow::Result<PathBuf> {
+pub(crate) fn get_export_path(root: &Path, name: &str) -> anyhow::Result<PathBuf> {
     let parts = name.split('/');
     let mut path = root.to_path_buf();
     for part in parts {
@@ -602,6 +1300,50 @@ fn validate_path_component(component: &str) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// The error of a failed request on `connection`, telling apart a sender that refused access.
+async fn get_failed(
+    e: GetError,
+    connection: &Connection,
+    endpoint: &Endpoint,
+    addr: &EndpointAddr,
+) -> anyhow::Error {
+    if refused_access(connection) {
+        refusal_reason(endpoint, addr).await.into()
+    } else {
+        show_get_error(e).into()
+    }
+}
+
+fn show_get_error(e: GetError) -> GetError {
+    match &e {
+        GetError::InitialNext { source, .. } => {
+            tracing::error!("initial connection error: {source}");
+        }
+        GetError::ConnectedNext { source, .. } => {
+            tracing::error!("connected error: {source}");
+        }
+        GetError::AtBlobHeaderNext { source, .. } => {
+            tracing::error!("reading blob header error: {source}");
+        }
+        GetError::Decode { source, .. } => {
+            tracing::error!("decoding error: {source}");
+        }
+        GetError::IrpcSend { source, .. } => {
+            tracing::error!("error sending over irpc: {source}");
+        }
+        GetError::AtClosingNext { source, .. } => {
+            tracing::error!("error at closing: {source}");
+        }
+        GetError::BadRequest { .. } => {
+            tracing::error!("bad request");
+        }
+        GetError::LocalFailure { source, .. } => {
+            tracing::error!("local failure {source:?}");
+        }
+    }
+    e
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -628,6 +1370,8 @@ mod tests {
             thumbnail: Some("data:image/jpeg;base64,e2e_test_thumbnail=".into()),
             mime_type: Some("text/plain".into()),
             items: None,
+            sender_name: None,
+            live: false,
         };
 
         let send_opts = SendOptions {
@@ -635,6 +1379,7 @@ mod tests {
             ticket_type: AddrInfoOptions::RelayAndAddresses,
             magic_ipv4_addr: None,
             magic_ipv6_addr: None,
+            ..Default::default()
         };
 
         // Start share
@@ -648,6 +1393,7 @@ mod tests {
             relay_mode: RelayModeOption::Default,
             magic_ipv4_addr: None,
             magic_ipv6_addr: None,
+            ..Default::default()
         };
 
         let fetched = fetch_metadata(result.ticket, recv_opts)
@@ -715,6 +1461,47 @@ mod tests {
         assert!(get_export_path(root, "subdir/../../etc/passwd").is_err());
     }
 
+    #[test]
+    fn select_entries_by_name_glob_and_index() {
+        let collection: Collection = [
+            "logs/app.log",
+            "logs/old/app.log",
+            "readme.md",
+            "src/main.rs",
+        ]
+        .into_iter()
+        .map(|name| (name, Hash::new(name)))
+        .collect();
+
+        let select = |selection: &[EntrySelector]| select_entries(&collection, selection);
+        assert_eq!(
+            select(&[EntrySelector::Glob("logs/*.log".into())]).unwrap(),
+            vec![0]
+        );
+        assert_eq!(
+            select(&[EntrySelector::Name("logs".into())]).unwrap(),
+            vec![0, 1]
+        );
+        assert_eq!(
+            select(&[
+                EntrySelector::Index(3),
+                EntrySelector::Name("readme.md".into())
+            ])
+            .unwrap(),
+            vec![2, 3]
+        );
+        assert!(select(&[EntrySelector::Name("log".into())]).is_err());
+        assert!(select(&[EntrySelector::Index(4)]).is_err());
+
+        let request = selection_request(Hash::new("root"), &[1]);
+        let offsets: Vec<u64> = request
+            .ranges
+            .iter_non_empty_infinite()
+            .map(|(offset, _)| offset)
+            .collect();
+        assert_eq!(offsets, vec![0, 1, 3]);
+    }
+
     #[test]
     fn get_export_path_blocks_backslash() {
         assert!(get_export_path(Path::new("/tmp/test"), "file\\name").is_err());
@@ -726,33 +1513,3 @@ mod tests {
         assert_eq!(p, PathBuf::from("/tmp/test/subdir/file.txt"));
     }
 }
-
-fn show_get_error(e: GetError) -> GetError {
-    match &e {
-        GetError::InitialNext { source, .. } => {
-            tracing::error!("initial connection error: {source}");
-        }
-        GetError::ConnectedNext { source, .. } => {
-            tracing::error!("connected error: {source}");
-        }
-        GetError::AtBlobHeaderNext { source, .. } => {
-            tracing::error!("reading blob header error: {source}");
-        }
-        GetError::Decode { source, .. } => {
-            tracing::error!("decoding error: {source}");
-        }
-        GetError::IrpcSend { source, .. } => {
-            tracing::error!("error sending over irpc: {source}");
-        }
-        GetError::AtClosingNext { source, .. } => {
-            tracing::error!("error at closing: {source}");
-        }
-        GetError::BadRequest { .. } => {
-            tracing::error!("bad request");
-        }
-        GetError::LocalFailure { source, .. } => {
-            tracing::error!("local failure {source:?}");
-        }
-    }
-    e
-}
@@ -0,0 +1,191 @@
+use crate::core::contacts::AddressBook;
+use iroh::EndpointId;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Where the download of one receiver stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ReceiverStatus {
+    Active,
+    Completed,
+    /// The receiver cancelled or the connection was lost before the data was sent.
+    Aborted,
+}
+
+/// # Description
+/// Progress of one receiver of a share, sent as `receiver-progress` and listed by
+/// [`ShareReceivers::snapshot`]. A receiver that reconnects shows up again under a new
+/// `connection_id`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiverProgress {
+    pub connection_id: u64,
+    pub endpoint_id: String,
+    /// Address book name of the receiver, if `SendOptions::contacts` knows it.
+    pub contact_name: Option<String>,
+    pub bytes_sent: u64,
+    pub total_bytes: u64,
+    pub speed_bps: f64,
+    pub status: ReceiverStatus,
+}
+
+/// # Description
+/// The receivers of a running share, kept up to date by its progress events. Receivers that
+/// finished stay listed with their final status until the share stops. Clones share the same
+/// list.
+#[derive(Debug, Clone, Default)]
+pub struct ShareReceivers {
+    inner: Arc<Mutex<ReceiversState>>,
+}
+
+#[derive(Debug, Default)]
+struct ReceiversState {
+    contacts: Option<AddressBook>,
+    /// Remote endpoint of every admitted open connection, known before it requests any data.
+    endpoints: HashMap<u64, EndpointId>,
+    receivers: BTreeMap<u64, ReceiverProgress>,
+}
+
+impl ShareReceivers {
+    pub(crate) fn new(contacts: Option<AddressBook>) -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(ReceiversState {
+                contacts,
+                ..ReceiversState::default()
+            })),
+        }
+    }
+
+    /// All receivers that requested data so far, in the order they connected.
+    pub fn snapshot(&self) -> Vec<ReceiverProgress> {
+        self.inner
+            .lock()
+            .unwrap()
+            .receivers
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn connected(&self, connection_id: u64, endpoint_id: EndpointId) {
+        self.inner
+            .lock()
+            .unwrap()
+            .endpoints
+            .insert(connection_id, endpoint_id);
+    }
+
+    /// The remote endpoint of an open connection.
+    pub(crate) fn endpoint_id(&self, connection_id: u64) -> Option<EndpointId> {
+        self.inner
+            .lock()
+            .unwrap()
+            .endpoints
+            .get(&connection_id)
+            .copied()
+    }
+
+    /// Records the progress of a data request and returns the updated entry. Connections whose
+    /// endpoint is unknown are not tracked.
+    pub(crate) fn progress(
+        &self,
+        connection_id: u64,
+        bytes_sent: u64,
+        total_bytes: u64,
+        speed_bps: f64,
+    ) -> Option<ReceiverProgress> {
+        let mut state = self.inner.lock().unwrap();
+        let state = &mut *state;
+        let endpoint_id = *state.endpoints.get(&connection_id)?;
+        let receiver = state
+            .receivers
+            .entry(connection_id)
+            .or_insert_with(|| ReceiverProgress {
+                connection_id,
+                endpoint_id: endpoint_id.to_string(),
+                contact_name: state
+                    .contacts
+                    .as_ref()
+                    .and_then(|contacts| contacts.name_for(&endpoint_id))
+                    .map(str::to_string),
+                bytes_sent: 0,
+                total_bytes,
+                speed_bps: 0.0,
+                status: ReceiverStatus::Active,
+            });
+        receiver.bytes_sent = bytes_sent;
+        receiver.total_bytes = total_bytes;
+        receiver.speed_bps = speed_bps;
+        receiver.status = ReceiverStatus::Active;
+        Some(receiver.clone())
+    }
+
+    /// Marks the download on this connection as done and returns the updated entry, if it was
+    /// still active.
+    pub(crate) fn finished(
+        &self,
+        connection_id: u64,
+        status: ReceiverStatus,
+    ) -> Option<ReceiverProgress> {
+        let mut state = self.inner.lock().unwrap();
+        let receiver = state.receivers.get_mut(&connection_id)?;
+        if receiver.status != ReceiverStatus::Active {
+            return None;
+        }
+        receiver.status = status;
+        if status == ReceiverStatus::Completed {
+            receiver.bytes_sent = receiver.total_bytes;
+        }
+        receiver.speed_bps = 0.0;
+        Some(receiver.clone())
+    }
+
+    /// Forgets the endpoint of a closed connection. A download still active on it is aborted,
+    /// and the updated entry returned.
+    pub(crate) fn closed(&self, connection_id: u64) -> Option<ReceiverProgress> {
+        self.inner.lock().unwrap().endpoints.remove(&connection_id);
+        self.finished(connection_id, ReceiverStatus::Aborted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn receivers_are_tracked_per_connection() {
+        let alice = iroh::SecretKey::generate().public();
+        let bob = iroh::SecretKey::generate().public();
+        let receivers = ShareReceivers::new(None);
+
+        assert_eq!(
+            receivers.progress(7, 1, 10, 1.0),
+            None,
+            "unknown connection"
+        );
+        receivers.connected(1, alice);
+        receivers.connected(2, bob);
+        assert!(receivers.snapshot().is_empty());
+
+        let progress = receivers.progress(1, 4, 10, 2.0).unwrap();
+        assert_eq!(progress.endpoint_id, alice.to_string());
+        assert_eq!(progress.status, ReceiverStatus::Active);
+        receivers.progress(2, 6, 10, 3.0).unwrap();
+
+        let done = receivers.finished(1, ReceiverStatus::Completed).unwrap();
+        assert_eq!(done.bytes_sent, 10);
+        assert_eq!(receivers.finished(1, ReceiverStatus::Aborted), None);
+        assert_eq!(receivers.closed(1), None);
+
+        let lost = receivers.closed(2).unwrap();
+        assert_eq!(lost.status, ReceiverStatus::Aborted);
+        assert_eq!(lost.bytes_sent, 6);
+
+        let snapshot = receivers.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        assert_eq!(snapshot[0].status, ReceiverStatus::Completed);
+        assert_eq!(snapshot[1].status, ReceiverStatus::Aborted);
+    }
+}
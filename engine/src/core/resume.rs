@@ -0,0 +1,165 @@
+use super::types::{EntrySelector, MirrorOptions};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// # Description
+/// Describes a receive that did not finish. The partial blob store is kept on disk next to this record
+/// so that the download can be picked up again with `resume_download` after an app restart.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterruptedDownload {
+    pub ticket: String,
+    pub hash: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_dir: Option<PathBuf>,
+    /// Total payload size, known once the sender answered the size probe.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload_size: Option<u64>,
+    /// Entries the receive was limited to; empty for the whole collection.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub selection: Vec<EntrySelector>,
+    /// Set if the receive updates an earlier copy in `output_dir`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorOptions>,
+    /// Set if the receive asked for the latest version of a live share. The record is then
+    /// stored under that version's hash instead of the ticket's.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub latest: bool,
+    /// Bytes currently held by the partial store, filled in when scanning.
+    #[serde(default)]
+    pub bytes_on_disk: u64,
+    /// Unix timestamp (seconds) of the first attempt.
+    pub started_at: u64,
+}
+
+impl InterruptedDownload {
+    pub fn new(ticket: String, hash: String, output_dir: Option<PathBuf>) -> Self {
+        let started_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        Self {
+            ticket,
+            hash,
+            output_dir,
+            payload_size: None,
+            selection: Vec::new(),
+            mirror: None,
+            latest: false,
+            bytes_on_disk: 0,
+            started_at,
+        }
+    }
+}
+
+/// Root directory for partial receive stores.
+///
+/// Unlike the send side this is not under the temp dir, so that partial data survives reboots
+/// on systems that clear it.
+pub fn partial_downloads_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("sendme")
+        .join("partial")
+}
+
+/// Directory of the FsStore used for the collection with the given hex hash.
+pub fn partial_store_dir(hash: &str) -> PathBuf {
+    partial_downloads_dir().join(hash)
+}
+
+fn record_path(hash: &str) -> PathBuf {
+    partial_downloads_dir().join(format!("{hash}.json"))
+}
+
+pub(crate) async fn write_record(record: &InterruptedDownload) -> anyhow::Result<()> {
+    let path = record_path(&record.hash);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    let bytes = serde_json::to_vec_pretty(record)?;
+    tokio::fs::write(&path, bytes).await?;
+    Ok(())
+}
+
+/// Reads the record for a partial download, if there is one.
+pub async fn read_record(hash: &str) -> anyhow::Result<Option<InterruptedDownload>> {
+    match tokio::fs::read(record_path(hash)).await {
+        Ok(bytes) => Ok(Some(serde_json::from_slice(&bytes)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Removes the partial store and its record. Missing files are not an error.
+pub async fn discard_interrupted_download(hash: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !hash.is_empty() && hash.chars().all(|c| c.is_ascii_hexdigit()),
+        "invalid hash: {hash}"
+    );
+    match tokio::fs::remove_dir_all(partial_store_dir(hash)).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    match tokio::fs::remove_file(record_path(hash)).await {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => return Err(e.into()),
+    }
+    Ok(())
+}
+
+/// # Description
+/// Scans the partial downloads directory for receives that were interrupted (crash, quit, network loss).
+/// Records whose store directory is gone are dropped.
+/// # Returns
+/// The interrupted downloads, oldest first.
+pub async fn list_interrupted_downloads() -> anyhow::Result<Vec<InterruptedDownload>> {
+    let root = partial_downloads_dir();
+    let mut entries = match tokio::fs::read_dir(&root).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut out = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().and_then(|x| x.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(hash) = path.file_stem().and_then(|x| x.to_str()) else {
+            continue;
+        };
+        let mut record = match read_record(hash).await {
+            Ok(Some(record)) => record,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("skipping unreadable resume record {}: {}", hash, e);
+                continue;
+            }
+        };
+        let store_dir = partial_store_dir(hash);
+        if !store_dir.is_dir() {
+            let _ = tokio::fs::remove_file(&path).await;
+            continue;
+        }
+        record.bytes_on_disk = tokio::task::spawn_blocking(move || dir_size(&store_dir)).await?;
+        out.push(record);
+    }
+
+    out.sort_by_key(|r| r.started_at);
+    Ok(out)
+}
+
+fn dir_size(path: &Path) -> u64 {
+    walkdir::WalkDir::new(path)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum()
+}
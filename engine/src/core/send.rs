@@ -1,9 +1,20 @@
+use crate::core::access::{AuthProtocol, MetadataDenied, ShareAccess, AUTH_ALPN};
+use crate::core::attributes::{
+    link_target_within, AttributesManifest, EntryAttributes, ATTRIBUTES_ENTRY,
+};
+use crate::core::contacts::AddressBook;
+use crate::core::decisions::PendingDecisions;
+use crate::core::events::TransferEvent;
+use crate::core::ratelimit::ShareRateLimiter;
+use crate::core::receivers::{ReceiverStatus, ShareReceivers};
 use crate::core::types::{
-    apply_options, get_or_create_secret, AddrInfoOptions, AppHandle, FileMetadata, SendOptions,
-    SendResult,
+    apply_options, get_or_create_secret, AddrInfoOptions, AppHandle, EntrySkipReason, FileMetadata,
+    FilePreviewItem, LinkSkipReason, SendOptions, SendResult, ShareExpiryReason, SkippedEntry,
+    SkippedLink, SymlinkPolicy,
 };
 use anyhow::{ensure, Context};
 use data_encoding::HEXLOWER;
+use ignore::{overrides::OverrideBuilder, WalkBuilder};
 use iroh::endpoint::presets;
 use iroh::protocol::{AcceptError, ProtocolHandler};
 use iroh::{address_lookup::pkarr::PkarrPublisher, endpoint::RelayMode, Endpoint};
@@ -14,30 +25,57 @@ use iroh_blobs::{
         Store, TempTag,
     },
     format::collection::Collection,
-    provider::events::{ConnectMode, EventMask, EventSender, RequestMode},
+    protocol::ChunkRangesSeq,
+    provider::events::{
+        AbortReason, ConnectMode, EventMask, EventSender, RequestMode, ThrottleMode,
+    },
     store::fs::FsStore,
     ticket::BlobTicket,
-    BlobFormat, BlobsProtocol,
+    BlobFormat, BlobsProtocol, Hash,
 };
 use n0_future::StreamExt;
 use n0_future::{task::AbortOnDropHandle, BufferedStreamExt};
 use rand::RngExt;
 use std::io::ErrorKind;
 use std::{
+    collections::HashMap,
     path::{Component, Path, PathBuf},
-    time::{Duration, Instant},
+    time::{Duration, Instant, SystemTime},
+};
+use tokio::{
+    select,
+    sync::{mpsc, watch},
 };
-use tokio::{select, sync::mpsc};
-use walkdir::WalkDir;
 
 // To avoid encoding thumbnail into ticket causing excessively long tickets, we use a custom metadata protocol to
 // send metadata seprately from the file data. After the receive end sticks the ticket, a seprate connection will
 // be made to fetch the metadata.
 pub const METADATA_ALPN: &[u8] = b"sendme/metadata/1";
 
-#[derive(Debug, Clone)]
+/// Ignore file read from shared directories with `SendOptions::use_ignore_files`.
+pub const SENDME_IGNORE_FILE: &str = ".sendmeignore";
+
+/// ALPN of the protocol that answers with the root hash of the current version of a share.
+/// Receivers with `ReceiveOptions::latest` set ask it before fetching.
+pub const LATEST_ALPN: &[u8] = b"sendme/latest/1";
+
+#[derive(Clone)]
 struct MetadataProtocol {
-    metadata: Option<FileMetadata>,
+    /// Updated by live shares.
+    metadata: watch::Receiver<Option<FileMetadata>>,
+    access: ShareAccess,
+    /// Whether receivers need `SendOptions::confirm_receivers` before they see the metadata.
+    confirm_receivers: bool,
+    app_handle: AppHandle,
+}
+
+impl std::fmt::Debug for MetadataProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MetadataProtocol")
+            .field("access", &self.access)
+            .field("confirm_receivers", &self.confirm_receivers)
+            .finish_non_exhaustive()
+    }
 }
 
 impl ProtocolHandler for MetadataProtocol {
@@ -78,14 +116,35 @@ impl ProtocolHandler for MetadataProtocol {
 
         tracing::debug!("metadata request marker received");
 
-        let payload = self.metadata.clone().ok_or_else(|| {
-            AcceptError::from_err(std::io::Error::new(
-                ErrorKind::NotFound,
-                "metadata unavailable",
-            ))
-        })?;
-
-        let meta_bytes = serde_json::to_vec(&payload).map_err(AcceptError::from_err)?;
+        // Receivers that may not fetch the share learn only why, not what it contains.
+        let endpoint_id = connection.remote_id();
+        let meta_bytes = match self.access.check(&endpoint_id) {
+            Ok(()) => {
+                let mut payload = self.metadata.borrow().clone().ok_or_else(|| {
+                    AcceptError::from_err(std::io::Error::new(
+                        ErrorKind::NotFound,
+                        "metadata unavailable",
+                    ))
+                })?;
+                if self.confirm_receivers && self.access.confirmed(&endpoint_id) != Some(true) {
+                    tracing::info!(%endpoint_id, "metadata withheld until the receiver is confirmed");
+                    payload = confirmation_stub(&payload);
+                }
+                serde_json::to_vec(&payload)
+            }
+            Err(denied) => {
+                tracing::info!(%endpoint_id, ?denied, "metadata request denied");
+                emit(
+                    &self.app_handle,
+                    TransferEvent::ConnectionRejected {
+                        endpoint_id: endpoint_id.to_string(),
+                        reason: denied,
+                    },
+                );
+                serde_json::to_vec(&MetadataDenied { denied })
+            }
+        }
+        .map_err(AcceptError::from_err)?;
         const MAX_METADATA_BYTES: usize = 8 * 1024 * 1024;
         if meta_bytes.len() > MAX_METADATA_BYTES {
             return Err(AcceptError::from_err(std::io::Error::new(
@@ -129,42 +188,298 @@ impl ProtocolHandler for MetadataProtocol {
     }
 }
 
-fn emit_event(app_handle: &AppHandle, event_name: &str) {
-    if let Some(handle) = app_handle {
-        if let Err(e) = handle.emit_event(event_name) {
-            tracing::warn!("Failed to emit event {}: {}", event_name, e);
+#[derive(Debug, Clone)]
+struct LatestProtocol {
+    hash: watch::Receiver<Hash>,
+    access: ShareAccess,
+}
+
+impl ProtocolHandler for LatestProtocol {
+    /// # Description
+    /// Handles incoming connections on the latest protocol.
+    /// It reads a request marker (1 byte) from the client, responds with the 32 byte root hash of the current version, and waits for the client to close the connection.
+    async fn accept(&self, connection: iroh::endpoint::Connection) -> Result<(), AcceptError> {
+        if self.access.check(&connection.remote_id()).is_err() {
+            connection.close(iroh_blobs::protocol::ERR_PERMISSION, b"permission");
+            return Ok(());
         }
+        let (mut send_stream, mut recv_stream) =
+            match tokio::time::timeout(Duration::from_secs(30), connection.accept_bi()).await {
+                Ok(Ok(streams)) => streams,
+                Ok(Err(err)) => return Err(err.into()),
+                Err(_) => {
+                    tracing::debug!("latest accept_bi timeout (benign)");
+                    return Ok(());
+                }
+            };
+
+        let mut req = [0u8; 1];
+        tokio::time::timeout(Duration::from_secs(10), recv_stream.read_exact(&mut req))
+            .await
+            .map_err(|_| {
+                AcceptError::from_err(std::io::Error::new(
+                    ErrorKind::TimedOut,
+                    "latest request read timeout",
+                ))
+            })?
+            .map_err(AcceptError::from_err)?;
+
+        let hash = *self.hash.borrow();
+        send_stream
+            .write_all(hash.as_bytes())
+            .await
+            .map_err(AcceptError::from_err)?;
+        send_stream.finish().map_err(AcceptError::from_err)?;
+
+        let mut eof_buf = [0u8; 1];
+        let _ = tokio::time::timeout(Duration::from_secs(30), recv_stream.read(&mut eof_buf)).await;
+
+        tracing::debug!(%hash, "latest version sent");
+
+        Ok(())
     }
 }
 
-fn emit_progress_event(
-    app_handle: &AppHandle,
-    bytes_transferred: u64,
-    total_size: u64,
-    speed: f64,
+/// # Description
+/// The metadata announced to a receiver the app has not accepted yet: how much is shared, but
+/// not the names, previews or types of what it is.
+fn confirmation_stub(metadata: &FileMetadata) -> FileMetadata {
+    FileMetadata {
+        file_name: String::new(),
+        item_count: metadata.item_count,
+        size: metadata.size,
+        thumbnail: None,
+        mime_type: None,
+        items: None,
+        sender_name: None,
+        live: metadata.live,
+    }
+}
+
+/// A chunk of a share waiting for the rate limiter to let it through.
+type ThrottledChunk = irpc::WithChannels<
+    iroh_blobs::provider::events::Throttle,
+    iroh_blobs::provider::events::ProviderProto,
+>;
+
+/// # Description
+/// Answers the per chunk throttle requests of the share's provider and passes its other events
+/// on to the progress loop, so that the loop is not woken up for every chunk. Without a rate
+/// limit chunks are let through right away. With one, the chunks of each connection are paced
+/// by `pace_connection`, which also applies to connections accepted before the limit was set.
+async fn answer_throttles(
+    mut recv: mpsc::Receiver<iroh_blobs::provider::events::ProviderMessage>,
+    progress: mpsc::Sender<iroh_blobs::provider::events::ProviderMessage>,
+    rate_limiter: ShareRateLimiter,
+    receivers: ShareReceivers,
 ) {
-    if let Some(handle) = app_handle {
-        let event_name = "transfer-progress";
+    let mut pacers: HashMap<u64, mpsc::UnboundedSender<ThrottledChunk>> = HashMap::new();
+    while let Some(item) = recv.recv().await {
+        let msg = match item {
+            iroh_blobs::provider::events::ProviderMessage::Throttle(msg) => msg,
+            other => {
+                if let iroh_blobs::provider::events::ProviderMessage::ConnectionClosed(msg) = &other
+                {
+                    // The pacer ends once it let the chunks it holds through.
+                    pacers.remove(&msg.inner.connection_id);
+                }
+                if progress.send(other).await.is_err() {
+                    break;
+                }
+                continue;
+            }
+        };
+        if !rate_limiter.is_limited() {
+            msg.tx.send(Ok(())).await.ok();
+            continue;
+        }
+        let connection_id = msg.inner.connection_id;
+        let pacer = pacers.entry(connection_id).or_insert_with(|| {
+            let (tx, rx) = mpsc::unbounded_channel();
+            n0_future::task::spawn(pace_connection(
+                rx,
+                receivers.endpoint_id(connection_id),
+                rate_limiter.clone(),
+            ));
+            tx
+        });
+        pacer.send(msg).ok();
+    }
+}
 
-        // Keep legacy payload format for frontend compatibility: "bytes:total:speed"
-        let payload = format!("{}:{}:{}", bytes_transferred, total_size, speed);
-        if let Err(e) = handle.emit_event_with_payload(event_name, &payload) {
-            tracing::warn!("Failed to emit progress event: {}", e);
+/// Lets the chunks of one connection through in order, each once the data sent before it is
+/// paid off under the share's rate limits.
+async fn pace_connection(
+    mut chunks: mpsc::UnboundedReceiver<ThrottledChunk>,
+    receiver: Option<iroh::EndpointId>,
+    rate_limiter: ShareRateLimiter,
+) {
+    while let Some(chunk) = chunks.recv().await {
+        let delay = rate_limiter.delay(receiver, chunk.inner.size);
+        if !delay.is_zero() {
+            tokio::time::sleep(delay).await;
         }
+        chunk.tx.send(Ok(())).await.ok();
     }
 }
 
-fn emit_active_connection_count(app_handle: &AppHandle, count: usize) {
-    if let Some(handle) = app_handle {
-        let event_name = "active-connection-count";
-        let payload = count.to_string();
+/// What a share announces besides its blobs; updated by live shares.
+struct Published {
+    hash: watch::Sender<Hash>,
+    metadata: watch::Sender<Option<FileMetadata>>,
+    /// Total size of the shared files, used by the provide progress.
+    size: watch::Sender<u64>,
+}
+
+/// The state of a share with `SendOptions::watch_interval` set, kept between rescans.
+struct LiveShare {
+    paths: Vec<PathBuf>,
+    preserve_attributes: bool,
+    strict: bool,
+    collect_options: CollectOptions,
+    cache: ImportCache,
+    published: Published,
+    interval: Duration,
+}
+
+impl LiveShare {
+    /// # Description
+    /// Rescans the shared paths every `interval` and publishes a new version, with refreshed
+    /// metadata and progress total, whenever the collection changed. Only files whose size or modification time changed are imported
+    /// again. A failed rescan keeps the current version.
+    async fn run(mut self, db: Store, app_handle: AppHandle) {
+        // Keeps the published version alive; the first one is held by the `SendResult`.
+        let mut _current: Option<TempTag> = None;
+        let mut version = 0u64;
+        loop {
+            tokio::time::sleep(self.interval).await;
+            let imported = match import_paths(
+                self.paths.clone(),
+                &db,
+                self.preserve_attributes,
+                self.strict,
+                &self.collect_options,
+                &mut self.cache,
+            )
+            .await
+            {
+                Ok(imported) => imported,
+                Err(e) => {
+                    tracing::warn!("failed to update live share: {}", e);
+                    continue;
+                }
+            };
+            let hash = imported.temp_tag.hash();
+            if hash == *self.published.hash.borrow() {
+                continue;
+            }
 
-        if let Err(e) = handle.emit_event_with_payload(event_name, &payload) {
-            tracing::warn!("Failed to emit active connection count event: {}", e);
+            version += 1;
+            tracing::info!(%hash, version, "live share updated");
+            self.published.metadata.send_modify(|metadata| {
+                if let Some(metadata) = metadata {
+                    refresh_metadata(metadata, &self.paths, &imported);
+                }
+            });
+            self.published.size.send_replace(imported.size);
+            self.published.hash.send_replace(hash);
+            if !imported.skipped.is_empty() {
+                emit(
+                    &app_handle,
+                    TransferEvent::ShareWarnings {
+                        skipped: imported.skipped,
+                    },
+                );
+            }
+            emit(
+                &app_handle,
+                TransferEvent::ShareUpdated {
+                    hash: hash.to_hex().to_string(),
+                    version,
+                    size: imported.size,
+                    file_count: imported.collection.len() as u64,
+                },
+            );
+            _current = Some(imported.temp_tag);
         }
     }
 }
 
+/// # Description
+/// Brings the announced metadata of a live share in line with a new version: the total size,
+/// and the preview items with their sizes and count. Roots without files anymore are dropped
+/// from the items; thumbnails and MIME types are the ones announced when the share started.
+fn refresh_metadata(metadata: &mut FileMetadata, paths: &[PathBuf], imported: &ImportedPaths) {
+    metadata.size = imported.size;
+    metadata.item_count = imported
+        .root_sizes
+        .iter()
+        .filter(|size| **size > 0)
+        .count()
+        .max(1) as u32;
+    let Some(items) = metadata.items.take() else {
+        return;
+    };
+    let mut previous: HashMap<String, FilePreviewItem> = items
+        .into_iter()
+        .map(|item| (item.file_name.clone(), item))
+        .collect();
+    // Named like `collect_preview_items` of the app, which built the first items.
+    let mut seen = std::collections::BTreeMap::new();
+    let mut items: Vec<FilePreviewItem> = paths
+        .iter()
+        .zip(&imported.root_sizes)
+        .filter_map(|(path, size)| {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .filter(|name| !name.is_empty())
+                .unwrap_or("item");
+            let file_name = dedup_name(name, &mut seen);
+            if *size == 0 {
+                return None;
+            }
+            let item = previous.remove(&file_name);
+            Some(FilePreviewItem {
+                size: *size,
+                thumbnail: item.as_ref().and_then(|item| item.thumbnail.clone()),
+                mime_type: item.and_then(|item| item.mime_type),
+                file_name,
+            })
+        })
+        .collect();
+    items.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+    metadata.items = Some(items);
+}
+
+fn emit(app_handle: &AppHandle, event: TransferEvent) {
+    if let Some(handle) = app_handle {
+        if let Err(e) = handle.emit(&event) {
+            tracing::warn!("Failed to emit event {}: {}", event.name(), e);
+        }
+    }
+}
+
+fn emit_progress_event(
+    app_handle: &AppHandle,
+    bytes_transferred: u64,
+    total_bytes: u64,
+    speed_bps: f64,
+) {
+    emit(
+        app_handle,
+        TransferEvent::TransferProgress {
+            bytes_transferred,
+            total_bytes,
+            speed_bps,
+        },
+    );
+}
+
+fn emit_active_connection_count(app_handle: &AppHandle, count: usize) {
+    emit(app_handle, TransferEvent::ActiveConnectionCount { count });
+}
+
 /// Deprecated: `start_share_items` should be used instead which supports
 /// sharing multiple files/directories at once and provides better filename handling.
 ///
@@ -188,7 +503,10 @@ pub async fn start_share_items(
 ) -> anyhow::Result<SendResult> {
     ensure!(!paths.is_empty(), "no paths provided for sharing");
 
-    let secret_key = get_or_create_secret()?;
+    let secret_key = match options.secret_key.clone() {
+        Some(key) => key,
+        None => get_or_create_secret()?,
+    };
     let relay_mode: RelayMode = options.relay_mode.clone().into();
     let mut builder = Endpoint::builder(presets::N0)
         .alpns(vec![iroh_blobs::ALPN.to_vec(), METADATA_ALPN.to_vec()])
@@ -214,6 +532,7 @@ pub async fn start_share_items(
     let blobs_data_dir2 = blobs_data_dir.clone();
     let (progress_tx, progress_rx) = mpsc::channel(64);
     let app_handle_clone = app_handle.clone();
+    let metadata_app_handle = app_handle.clone();
     let is_collection = canonical_paths.len() > 1;
     let entry_type_for_progress = if is_collection {
         "collection".to_string()
@@ -223,37 +542,119 @@ pub async fn start_share_items(
         "file".to_string()
     };
     let entry_type = entry_type_for_progress.clone();
+    let preserve_attributes = options.preserve_attributes;
+    let strict = options.strict;
+    let collect_options = CollectOptions::from(&options);
+    let watched_paths = canonical_paths.clone();
+    let (downloads_tx, downloads_rx) = watch::channel(0u64);
+    let access = ShareAccess::new(
+        options.allowed_receivers.clone(),
+        options.password.as_deref(),
+    );
+    let confirm_receivers = options.confirm_receivers.is_some();
+    let confirmation = options
+        .confirm_receivers
+        .map(|timeout| ReceiverConfirmation {
+            timeout,
+            decisions: options.connection_decisions.clone(),
+            contacts: options.contacts.clone(),
+        });
+    let receivers = ShareReceivers::new(options.contacts.clone());
+    let progress_receivers = receivers.clone();
+    let rate_limiter = ShareRateLimiter::new(options.rate_limits);
+    let progress_rate_limiter = rate_limiter.clone();
+    let throttle_rate_limiter = rate_limiter.clone();
+    let throttle_receivers = receivers.clone();
+    let watch_collect_options = collect_options.clone();
+    let mut metadata = metadata;
+    if let (Some(_), Some(metadata)) = (options.watch_interval, metadata.as_mut()) {
+        metadata.live = true;
+    }
 
-    let setup = async move {
-        tokio::fs::create_dir_all(&blobs_data_dir2).await?;
-        let endpoint = builder.bind().await?;
-        let store = FsStore::load(&blobs_data_dir2).await?;
+    tokio::fs::create_dir_all(&blobs_data_dir).await?;
+    let endpoint = builder.bind().await?;
+    let store = FsStore::load(&blobs_data_dir).await?;
+    let endpoint2 = endpoint.clone();
+    let store2 = store.clone();
 
-        let blobs = BlobsProtocol::new(
-            &store,
-            Some(EventSender::new(
-                progress_tx,
-                EventMask {
-                    connected: ConnectMode::Notify,
-                    get: RequestMode::NotifyLog,
-                    ..EventMask::DEFAULT
+    let setup = async move {
+        // Every chunk asks `answer_throttles`, so that a rate limit set while the share runs
+        // also paces the connections that are already open.
+        let events = EventMask {
+            connected: ConnectMode::Intercept,
+            get: RequestMode::NotifyLog,
+            throttle: ThrottleMode::Intercept,
+            ..EventMask::DEFAULT
+        };
+        let (throttle_tx, throttle_rx) = mpsc::channel(64);
+        let throttle_handle = AbortOnDropHandle::new(n0_future::task::spawn(answer_throttles(
+            throttle_rx,
+            progress_tx,
+            throttle_rate_limiter,
+            throttle_receivers,
+        )));
+        let blobs = BlobsProtocol::new(&store, Some(EventSender::new(throttle_tx, events)));
+
+        let mut cache = ImportCache::default();
+        let import_result = import_paths(
+            canonical_paths,
+            blobs.store(),
+            preserve_attributes,
+            strict,
+            &collect_options,
+            &mut cache,
+        )
+        .await?;
+        let size = import_result.size;
+        if !import_result.skipped.is_empty() {
+            emit(
+                &app_handle_clone,
+                TransferEvent::ShareWarnings {
+                    skipped: import_result.skipped.clone(),
                 },
-            )),
-        );
-
-        let import_result = import_paths(canonical_paths, blobs.store()).await?;
-        let (ref _temp_tag, size, ref _collection) = import_result;
+            );
+        }
 
+        let (size_tx, size_rx) = watch::channel(size);
         let progress_handle = n0_future::task::spawn(show_provide_progress_with_logging(
             progress_rx,
             app_handle_clone,
-            size,
+            size_rx,
             entry_type_for_progress,
+            downloads_tx,
+            access.clone(),
+            confirmation,
+            progress_receivers,
+            progress_rate_limiter,
         ));
 
+        let (hash_tx, hash_rx) = watch::channel(import_result.temp_tag.hash());
+        let (metadata_tx, metadata_rx) = watch::channel(metadata);
+        let published = Published {
+            hash: hash_tx,
+            metadata: metadata_tx,
+            size: size_tx,
+        };
+
         let router = iroh::protocol::Router::builder(endpoint)
             .accept(iroh_blobs::ALPN, blobs.clone())
-            .accept(METADATA_ALPN, MetadataProtocol { metadata })
+            .accept(
+                METADATA_ALPN,
+                MetadataProtocol {
+                    metadata: metadata_rx,
+                    access: access.clone(),
+                    confirm_receivers,
+                    app_handle: metadata_app_handle,
+                },
+            )
+            .accept(
+                LATEST_ALPN,
+                LatestProtocol {
+                    hash: hash_rx,
+                    access: access.clone(),
+                },
+            )
+            .accept(AUTH_ALPN, AuthProtocol { access })
             .spawn();
 
         let ep = router.endpoint();
@@ -267,20 +668,72 @@ pub async fn start_share_items(
         anyhow::Ok((
             router,
             import_result,
+            cache,
+            published,
             blobs_data_dir2,
             store,
             progress_handle,
+            throttle_handle,
         ))
     };
 
-    let (router, (temp_tag, size, _collection), _blobs_data_dir, store, progress_handle) = select! {
+    let (
+        router,
+        ImportedPaths {
+            temp_tag,
+            size,
+            collection,
+            skipped_links,
+            skipped,
+            ..
+        },
+        cache,
+        published,
+        _blobs_data_dir,
+        store,
+        progress_handle,
+        throttle_handle,
+    ) = select! {
         x = setup => x?,
-        _ = tokio::signal::ctrl_c() => {
+        _ = options.cancel_token.cancelled() => {
+            tracing::warn!("Share setup cancelled");
+            endpoint2.close().await;
+            store2.shutdown().await?;
+            if let Err(e) = tokio::fs::remove_dir_all(&blobs_data_dir).await {
+                tracing::warn!("Failed to clean up blobs directory: {}", e);
+            }
             anyhow::bail!("Operation cancelled");
         }
     };
     let hash = temp_tag.hash();
 
+    let watch_handle = options.watch_interval.map(|interval| {
+        let live = LiveShare {
+            paths: watched_paths,
+            preserve_attributes,
+            strict,
+            collect_options: watch_collect_options,
+            cache,
+            published,
+            interval,
+        };
+        let db: &Store = &store;
+        AbortOnDropHandle::new(n0_future::task::spawn(
+            live.run(db.clone(), app_handle.clone()),
+        ))
+    });
+
+    let limits_handle =
+        (options.expires_after.is_some() || options.max_downloads.is_some()).then(|| {
+            AbortOnDropHandle::new(n0_future::task::spawn(enforce_share_limits(
+                router.clone(),
+                options.expires_after,
+                options.max_downloads,
+                downloads_rx,
+                app_handle.clone(),
+            )))
+        });
+
     let mut addr = router.endpoint().addr();
 
     apply_options(&mut addr, options.ticket_type);
@@ -292,22 +745,56 @@ pub async fn start_share_items(
         hash: hash.to_hex().to_string(),
         size,
         entry_type: entry_type.to_string(),
+        file_count: collection.len() as u64,
+        skipped_links,
+        skipped,
+        receivers,
+        rate_limiter,
         router,
         temp_tag,
         blobs_data_dir,
         _progress_handle: AbortOnDropHandle::new(progress_handle),
+        _throttle_handle: throttle_handle,
+        _watch_handle: watch_handle,
+        _limits_handle: limits_handle,
         _store: store,
     })
 }
 
+/// The stored collection of a share, with what was left out of it.
+struct ImportedPaths {
+    temp_tag: TempTag,
+    size: u64,
+    /// The shared files only; the stored collection also has the attributes manifest if there
+    /// is one.
+    collection: Collection,
+    /// Size of the files imported from each of the paths, in the order of the paths.
+    root_sizes: Vec<u64>,
+    skipped_links: Vec<SkippedLink>,
+    skipped: Vec<SkippedEntry>,
+}
+
+/// Imports the paths and stores them as one collection. Entries that cannot be shared are
+/// skipped and reported, or fail the import if `strict` is set.
 async fn import_paths(
     paths: Vec<PathBuf>,
     db: &Store,
-) -> anyhow::Result<(TempTag, u64, Collection)> {
+    preserve_attributes: bool,
+    strict: bool,
+    collect_options: &CollectOptions,
+    cache: &mut ImportCache,
+) -> anyhow::Result<ImportedPaths> {
     use std::collections::BTreeMap;
 
-    let mut entries: Vec<(String, TempTag, u64)> = Vec::new();
+    let mut entries: Vec<(String, Hash, u64)> = Vec::new();
+    // Protect the imported blobs until the collection is stored.
+    let mut tags: Vec<TempTag> = Vec::new();
+    let mut next_cache = ImportCache::default();
     let mut name_seen: BTreeMap<String, usize> = BTreeMap::new();
+    let mut manifest = AttributesManifest::default();
+    let mut skipped_links = Vec::new();
+    let mut skipped = Vec::new();
+    let mut root_sizes = Vec::with_capacity(paths.len());
 
     for path in paths {
         let stem = path
@@ -316,65 +803,199 @@ async fn import_paths(
             .map(|s| s.to_string())
             .unwrap_or_else(|| "item".to_string());
 
-        let import = collect_path_files(&path, &stem)?;
+        let CollectedPaths {
+            files: import,
+            dirs,
+            links,
+            skipped_links: path_skipped_links,
+            skipped: path_skipped,
+        } = collect_path_files(&path, &stem, collect_options)?;
+        manifest.links.extend(links);
+        skipped_links.extend(path_skipped_links);
+        skipped.extend(path_skipped);
+        if preserve_attributes {
+            for (name, dir) in dirs {
+                match EntryAttributes::read(&dir) {
+                    Ok(attributes) => {
+                        manifest.dirs.insert(name, attributes);
+                    }
+                    Err(e) => tracing::warn!("failed to read attributes of {}: {}", name, e),
+                }
+            }
+        }
         if import.is_empty() {
             tracing::warn!("no valid files found in path {}, skipping", path.display());
+            skipped.push(SkippedEntry {
+                name: stem.clone(),
+                reason: EntrySkipReason::NoFiles,
+            });
         }
+        ensure_not_strict(strict, &skipped)?;
 
         let mut local = n0_future::stream::iter(import)
             .map(|(name, file_path)| {
                 let db = db.clone();
+                let stamp = FileStamp::read(&file_path);
+                let cached = stamp.and_then(|stamp| cache.get(&file_path, stamp));
                 async move {
+                    if let Some((hash, size)) = cached {
+                        return (name, file_path, stamp, Ok((hash, size, None)));
+                    }
                     let import = db.add_path_with_opts(AddPathOptions {
-                        path: file_path,
+                        path: file_path.clone(),
                         mode: ImportMode::TryReference,
                         format: iroh_blobs::BlobFormat::Raw,
                     });
                     let mut stream = import.stream().await;
                     let mut item_size = 0u64;
-                    let temp_tag = loop {
-                        let item = stream
-                            .next()
-                            .await
-                            .context("import stream ended without a tag")?;
+                    let imported = loop {
+                        let Some(item) = stream.next().await else {
+                            break Err(anyhow::anyhow!("import stream ended without a tag"));
+                        };
                         match item {
                             AddProgressItem::Size(size) => item_size = size,
-                            AddProgressItem::Done(tt) => break tt,
-                            AddProgressItem::Error(cause) => {
-                                anyhow::bail!("error importing {}:{}", name, cause)
+                            AddProgressItem::Done(tt) => {
+                                break Ok((tt.hash(), item_size, Some(tt)))
                             }
+                            AddProgressItem::Error(cause) => break Err(cause.into()),
                             _ => {}
                         }
                     };
-                    anyhow::Ok((name, temp_tag, item_size))
+                    (name, file_path, stamp, imported)
                 }
             })
             .buffered_unordered(num_cpus::get())
             .collect::<Vec<_>>()
-            .await
-            .into_iter()
-            .collect::<anyhow::Result<Vec<_>>>()?;
-
-        for (name, tag, size) in local.drain(..) {
+            .await;
+
+        let root_start = entries.len();
+        for (name, file_path, stamp, imported) in local.drain(..) {
+            let (hash, size, tag) = match imported {
+                Ok(imported) => imported,
+                Err(e) => {
+                    tracing::warn!("error importing {}: {}", name, e);
+                    skipped.push(SkippedEntry {
+                        name,
+                        reason: EntrySkipReason::Unreadable,
+                    });
+                    continue;
+                }
+            };
             let final_name = dedup_name(&name, &mut name_seen);
-            entries.push((final_name, tag, size));
+            if preserve_attributes {
+                match EntryAttributes::read(&file_path) {
+                    Ok(attributes) => {
+                        manifest.files.insert(final_name.clone(), attributes);
+                    }
+                    Err(e) => tracing::warn!("failed to read attributes of {}: {}", name, e),
+                }
+            }
+            if let Some(stamp) = stamp {
+                next_cache.insert(file_path, stamp, hash, size);
+            }
+            tags.extend(tag);
+            entries.push((final_name, hash, size));
         }
+        root_sizes.push(entries[root_start..].iter().map(|(_, _, size)| *size).sum());
     }
 
+    *cache = next_cache;
+    ensure_not_strict(strict, &skipped)?;
     entries.sort_by(|a, b| a.0.cmp(&b.0));
     ensure!(
         !entries.is_empty(),
         "no valid files found in provided paths"
     );
+    // Receivers take a last entry with the manifest's name for the manifest. A shared file with
+    // that name gets a suffix if the manifest follows it, or if it would be taken for one.
+    let with_manifest = preserve_attributes || !manifest.links.is_empty();
+    if let Some(index) = entries
+        .iter()
+        .position(|(name, _, _)| name == ATTRIBUTES_ENTRY)
+    {
+        if with_manifest || index == entries.len() - 1 {
+            let renamed = dedup_name(ATTRIBUTES_ENTRY, &mut name_seen);
+            if let Some(attributes) = manifest.files.remove(ATTRIBUTES_ENTRY) {
+                manifest.files.insert(renamed.clone(), attributes);
+            }
+            entries[index].0 = renamed;
+            entries.sort_by(|a, b| a.0.cmp(&b.0));
+        }
+    }
     let total_size = entries.iter().map(|(_, _, size)| *size).sum::<u64>();
-    let (collection, tags) = entries
+    let collection: Collection = entries
         .into_iter()
-        .map(|(name, tag, _)| ((name, tag.hash()), tag))
-        .unzip::<_, _, Collection, Vec<_>>();
+        .map(|(name, hash, _)| (name, hash))
+        .collect();
+
+    let mut stored = collection.clone();
+    let manifest_tag = if with_manifest {
+        let tag = db
+            .add_bytes(serde_json::to_vec(&manifest)?)
+            .temp_tag()
+            .await?;
+        stored.push(ATTRIBUTES_ENTRY.to_string(), tag.hash());
+        Some(tag)
+    } else {
+        None
+    };
 
-    let temp_tag = collection.clone().store(db).await?;
+    let temp_tag = stored.store(db).await?;
     drop(tags);
-    Ok((temp_tag, total_size, collection))
+    drop(manifest_tag);
+    Ok(ImportedPaths {
+        temp_tag,
+        size: total_size,
+        collection,
+        root_sizes,
+        skipped_links,
+        skipped,
+    })
+}
+
+/// Size and modification time of a file, to tell whether it changed since it was imported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    size: u64,
+    modified: SystemTime,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Option<Self> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some(Self {
+            size: metadata.len(),
+            modified: metadata.modified().ok()?,
+        })
+    }
+}
+
+/// Hashes of the files of the last import, so that a live share only imports files again
+/// whose size or modification time changed.
+#[derive(Debug, Default)]
+struct ImportCache {
+    files: HashMap<PathBuf, (FileStamp, Hash, u64)>,
+}
+
+impl ImportCache {
+    fn get(&self, path: &Path, stamp: FileStamp) -> Option<(Hash, u64)> {
+        match self.files.get(path) {
+            Some((cached, hash, size)) if *cached == stamp => Some((*hash, *size)),
+            _ => None,
+        }
+    }
+
+    fn insert(&mut self, path: PathBuf, stamp: FileStamp, hash: Hash, size: u64) {
+        self.files.insert(path, (stamp, hash, size));
+    }
+}
+
+fn ensure_not_strict(strict: bool, skipped: &[SkippedEntry]) -> anyhow::Result<()> {
+    if strict && !skipped.is_empty() {
+        let names: Vec<&str> = skipped.iter().map(|entry| entry.name.as_str()).collect();
+        anyhow::bail!("cannot share {}", names.join(", "));
+    }
+    Ok(())
 }
 
 pub fn canonicalized_path_to_string(
@@ -414,72 +1035,116 @@ pub fn canonicalized_path_to_string(
     Ok(path_str)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::path::Path;
-
-    #[cfg(unix)]
-    #[test]
-    fn canonicalized_path_rejects_backslash() {
-        let path = Path::new("system-systemd\\x2dcryptsetup.slice");
-        assert!(canonicalized_path_to_string(path, true).is_err());
-    }
-
-    #[test]
-    fn canonicalized_path_accepts_normal() {
-        let result = canonicalized_path_to_string(Path::new("subdir/file.txt"), true);
-        assert_eq!(result.unwrap(), "subdir/file.txt");
+/// Whether a get request asks for any shared file, as opposed to only the collection itself or
+/// the sizes of its entries.
+fn fetches_file_data(ranges: &ChunkRangesSeq) -> bool {
+    if *ranges == ChunkRangesSeq::verified_child_sizes() {
+        return false;
     }
+    // Offset 0 is the hash sequence and 1 the entry names, shared files start at 2.
+    ranges.is_infinite() || ranges.iter().skip(2).any(|child| !child.is_empty())
+}
 
-    #[test]
-    fn canonicalized_path_rejects_parent_traversal() {
-        assert!(canonicalized_path_to_string(Path::new("../etc/passwd"), true).is_err());
-    }
+/// # Description
+/// Stops the share by shutting its router down once `expires_after` passed or `max_downloads`
+/// downloads completed, whichever comes first, and emits `share-expired`.
+async fn enforce_share_limits(
+    router: iroh::protocol::Router,
+    expires_after: Option<Duration>,
+    max_downloads: Option<u64>,
+    mut downloads: watch::Receiver<u64>,
+    app_handle: AppHandle,
+) {
+    let expired = async {
+        match expires_after {
+            Some(duration) => tokio::time::sleep(duration).await,
+            None => std::future::pending().await,
+        }
+    };
+    let used_up = async {
+        match max_downloads {
+            Some(max) if downloads.wait_for(|count| *count >= max).await.is_ok() => {
+                // Give the last receiver a moment to read the end of the data.
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            _ => std::future::pending().await,
+        }
+    };
+    let reason = select! {
+        _ = expired => ShareExpiryReason::TimeLimit,
+        _ = used_up => ShareExpiryReason::DownloadLimit,
+    };
 
-    #[test]
-    fn canonicalized_path_rejects_absolute_when_relative() {
-        assert!(canonicalized_path_to_string(Path::new("/etc/passwd"), true).is_err());
+    tracing::info!(?reason, "share expired");
+    emit(&app_handle, TransferEvent::ShareExpired { reason });
+    if let Err(e) = router.shutdown().await {
+        tracing::warn!("Router shutdown error: {}", e);
     }
+}
 
-    #[cfg(unix)]
-    #[tokio::test]
-    async fn import_skips_invalid_files() {
-        use tempfile::TempDir;
-
-        let td = TempDir::new().unwrap();
-        let dir = td.path().join("testdir");
-        std::fs::create_dir_all(&dir).unwrap();
-        std::fs::write(dir.join("good.txt"), "hello").unwrap();
-        std::fs::write(dir.join(format!("bad{}file.txt", '\\')), "bad").unwrap();
-
-        let path = dir.canonicalize().unwrap();
-        let root = path.parent().unwrap();
-        let data_sources: Vec<(String, PathBuf)> = WalkDir::new(path.clone())
-            .into_iter()
-            .filter_map(|entry| {
-                let entry = entry.ok()?;
-                if !entry.file_type().is_file() {
-                    return None;
-                }
-                let path = entry.into_path();
-                let relative = path.strip_prefix(root).ok()?;
-                canonicalized_path_to_string(relative, true)
-                    .ok()
-                    .map(|name| (name, path))
-            })
-            .collect();
+/// How a share asks the app about new receivers, see `SendOptions::confirm_receivers`.
+#[derive(Debug, Clone)]
+struct ReceiverConfirmation {
+    timeout: Duration,
+    decisions: PendingDecisions<bool>,
+    contacts: Option<AddressBook>,
+}
 
-        assert_eq!(data_sources.len(), 1, "should skip file with backslash");
-        assert!(data_sources[0].0.contains("good.txt"));
+/// # Description
+/// Emits `connection-request` for a receiver and waits for the app's answer, which is
+/// remembered for the rest of the share. A request that is not answered in time denies this
+/// connection only.
+async fn confirm_receiver(
+    confirmation: ReceiverConfirmation,
+    access: ShareAccess,
+    app_handle: AppHandle,
+    endpoint_id: iroh::EndpointId,
+) -> Result<(), AbortReason> {
+    if app_handle.is_none() {
+        tracing::warn!(%endpoint_id, "receivers need confirmation but nobody can answer, denying");
+        return Err(AbortReason::Permission);
+    }
+    let (id, decision) = confirmation.decisions.request();
+    emit(
+        &app_handle,
+        TransferEvent::ConnectionRequest {
+            id,
+            endpoint_id: endpoint_id.to_string(),
+            contact_name: confirmation
+                .contacts
+                .as_ref()
+                .and_then(|contacts| contacts.name_for(&endpoint_id))
+                .map(str::to_string),
+        },
+    );
+    match tokio::time::timeout(confirmation.timeout, decision).await {
+        Ok(Ok(accepted)) => {
+            tracing::info!(%endpoint_id, accepted, "connection request answered");
+            access.confirm(endpoint_id, accepted);
+            if accepted {
+                Ok(())
+            } else {
+                Err(AbortReason::Permission)
+            }
+        }
+        Ok(Err(_)) | Err(_) => {
+            tracing::info!(%endpoint_id, "connection request not answered, denying");
+            Err(AbortReason::Permission)
+        }
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn show_provide_progress_with_logging(
     mut recv: mpsc::Receiver<iroh_blobs::provider::events::ProviderMessage>,
     app_handle: AppHandle,
-    total_collection_size: u64,
+    total_collection_size: watch::Receiver<u64>,
     entry_type: String,
+    downloads: watch::Sender<u64>,
+    access: ShareAccess,
+    confirmation: Option<ReceiverConfirmation>,
+    receivers: ShareReceivers,
+    rate_limiter: ShareRateLimiter,
 ) -> anyhow::Result<()> {
     use n0_future::FuturesUnordered;
     use std::sync::atomic::{AtomicUsize, Ordering};
@@ -517,9 +1182,56 @@ async fn show_provide_progress_with_logging(
                 };
 
                 match item {
-                    iroh_blobs::provider::events::ProviderMessage::ClientConnectedNotify(_msg) => {
+                    iroh_blobs::provider::events::ProviderMessage::ClientConnected(msg) => {
+                        let connection_id = msg.inner.connection_id;
+                        let decision = match msg.inner.endpoint_id {
+                            Some(endpoint_id) => access.check(&endpoint_id).map_err(|reason| {
+                                tracing::info!(%endpoint_id, ?reason, "connection rejected");
+                                emit(
+                                    &app_handle,
+                                    TransferEvent::ConnectionRejected {
+                                        endpoint_id: endpoint_id.to_string(),
+                                        reason,
+                                    },
+                                );
+                                AbortReason::Permission
+                            }),
+                            None => Err(AbortReason::Permission),
+                        };
+                        match (decision, msg.inner.endpoint_id, &confirmation) {
+                            // Waiting for the app must not hold up events of other receivers.
+                            (Ok(()), Some(endpoint_id), Some(confirmation))
+                                if access.confirmed(&endpoint_id).is_none() =>
+                            {
+                                let confirm = confirm_receiver(
+                                    confirmation.clone(),
+                                    access.clone(),
+                                    app_handle.clone(),
+                                    endpoint_id,
+                                );
+                                let receivers = receivers.clone();
+                                n0_future::task::spawn(async move {
+                                    let decision = confirm.await;
+                                    if decision.is_ok() {
+                                        receivers.connected(connection_id, endpoint_id);
+                                    }
+                                    msg.tx.send(decision).await.ok();
+                                });
+                            }
+                            (decision, endpoint_id, _) => {
+                                // Only admitted receivers are tracked, refused ones never get data.
+                                if let (Ok(()), Some(endpoint_id)) = (&decision, endpoint_id) {
+                                    receivers.connected(connection_id, endpoint_id);
+                                }
+                                msg.tx.send(decision).await.ok();
+                            }
+                        }
                     }
-                    iroh_blobs::provider::events::ProviderMessage::ConnectionClosed(_msg) => {
+                    iroh_blobs::provider::events::ProviderMessage::ConnectionClosed(msg) => {
+                        if let Some(receiver) = receivers.closed(msg.inner.connection_id) {
+                            emit(&app_handle, TransferEvent::ReceiverProgress(receiver));
+                        }
+                        rate_limiter.prune();
                     }
                     iroh_blobs::provider::events::ProviderMessage::GetRequestReceivedNotify(msg) => {
                         let is_sizes_probe_request =
@@ -529,6 +1241,7 @@ async fn show_provide_progress_with_logging(
 
                         let connection_id = msg.connection_id;
                         let request_id = msg.request_id;
+                        let is_download = fetches_file_data(&msg.request.ranges);
 
                         if !is_sizes_probe_request {
                             active_requests.fetch_add(1, Ordering::SeqCst);
@@ -545,6 +1258,10 @@ async fn show_provide_progress_with_logging(
                         let has_emitted_completed_task = has_emitted_completed.clone();
                         let last_request_time_task = last_request_time.clone();
                         let entry_type_task = entry_type.clone();
+                        let downloads_task = downloads.clone();
+                        let receivers_task = receivers.clone();
+                        // Live shares grow and shrink; a request counts against the size when it arrives.
+                        let total_collection_size = *total_collection_size.borrow();
 
                         let mut rx = msg.rx;
                         tasks.push(async move {
@@ -579,7 +1296,10 @@ async fn show_provide_progress_with_logging(
                                             emit_active_connection_count(&app_handle_task, active_count);
 
                                             if !has_emitted_started_task.swap(true, Ordering::SeqCst) {
-                                                emit_event(&app_handle_task, "transfer-started");
+                                                emit(&app_handle_task, TransferEvent::TransferStarted);
+                                            }
+                                            if let Some(receiver) = receivers_task.progress(connection_id, 0, total_collection_size, 0.0) {
+                                                emit(&app_handle_task, TransferEvent::ReceiverProgress(receiver));
                                             }
 
                                             transfer_started = true;
@@ -633,7 +1353,7 @@ async fn show_provide_progress_with_logging(
                                             emit_active_connection_count(&app_handle_task, active_count);
 
                                             if !has_emitted_started_task.swap(true, Ordering::SeqCst) {
-                                                emit_event(&app_handle_task, "transfer-started");
+                                                emit(&app_handle_task, TransferEvent::TransferStarted);
                                             }
                                             transfer_started = true;
                                         }
@@ -675,6 +1395,14 @@ async fn show_provide_progress_with_logging(
                                                 total_size,
                                                 speed_bps,
                                             );
+                                            if let Some(receiver) = receivers_task.progress(
+                                                connection_id,
+                                                transferred.min(total_size),
+                                                total_size,
+                                                speed_bps,
+                                            ) {
+                                                emit(&app_handle_task, TransferEvent::ReceiverProgress(receiver));
+                                            }
                                         }
                                     }
                                     iroh_blobs::provider::events::RequestUpdate::Completed(_m) => {
@@ -694,13 +1422,18 @@ async fn show_provide_progress_with_logging(
                                             let active_count = {
                                                 let mut states = transfer_states_task.lock().await;
                                                 states.remove(&(connection_id, request_id));
-                                                let active_count = states.len();
-                                                active_count
+                                                states.len()
                                             };
 
                                             emit_active_connection_count(&app_handle_task, active_count);
 
                                             request_completed = true;
+                                            if is_download {
+                                                downloads_task.send_modify(|count| *count += 1);
+                                            }
+                                            if let Some(receiver) = receivers_task.finished(connection_id, ReceiverStatus::Completed) {
+                                                emit(&app_handle_task, TransferEvent::ReceiverProgress(receiver));
+                                            }
 
                                             let completed = completed_requests_task.fetch_add(1, Ordering::SeqCst) + 1;
                                             let active = active_requests_task.load(Ordering::SeqCst);
@@ -739,12 +1472,11 @@ async fn show_provide_progress_with_logging(
                                                     && completed_after >= min_required
                                                     && !new_requests_arrived
                                                     && !has_active_transfers
-                                                    && !last_request_recent {
-                                                    if !has_emitted_completed_task
+                                                    && !last_request_recent
+                                                    && !has_emitted_completed_task
                                                         .swap(true, Ordering::SeqCst)
-                                                    {
-                                                        emit_event(&app_handle_task, "transfer-completed");
-                                                    }
+                                                {
+                                                    emit(&app_handle_task, TransferEvent::TransferCompleted);
                                                 }
                                             }
                                         }
@@ -762,12 +1494,15 @@ async fn show_provide_progress_with_logging(
                                             emit_active_connection_count(&app_handle_task, active_count);
 
                                             request_completed = true;
+                                            if let Some(receiver) = receivers_task.finished(connection_id, ReceiverStatus::Aborted) {
+                                                emit(&app_handle_task, TransferEvent::ReceiverProgress(receiver));
+                                            }
 
                                             let completed = completed_requests_task.fetch_add(1, Ordering::SeqCst) + 1;
                                             let active = active_requests_task.load(Ordering::SeqCst);
 
                                             if completed >= active {
-                                                emit_event(&app_handle_task, "transfer-failed");
+                                                emit(&app_handle_task, TransferEvent::TransferFailed);
                                             }
                                         }
                                     }
@@ -775,6 +1510,12 @@ async fn show_provide_progress_with_logging(
                             }
 
                             if transfer_started && !request_completed {
+                                if is_download {
+                                    downloads_task.send_modify(|count| *count += 1);
+                                }
+                                if let Some(receiver) = receivers_task.finished(connection_id, ReceiverStatus::Completed) {
+                                    emit(&app_handle_task, TransferEvent::ReceiverProgress(receiver));
+                                }
                                 let completed = completed_requests_task.fetch_add(1, Ordering::SeqCst) + 1;
                                 let active = active_requests_task.load(Ordering::SeqCst);
 
@@ -812,12 +1553,11 @@ async fn show_provide_progress_with_logging(
                                         && completed_after >= min_required
                                         && !new_requests_arrived
                                         && !has_active_transfers
-                                        && !last_request_recent {
-                                        if !has_emitted_completed_task
+                                        && !last_request_recent
+                                        && !has_emitted_completed_task
                                             .swap(true, Ordering::SeqCst)
-                                        {
-                                            emit_event(&app_handle_task, "transfer-completed");
-                                        }
+                                    {
+                                        emit(&app_handle_task, TransferEvent::TransferCompleted);
                                     }
                                 }
                             }
@@ -842,10 +1582,12 @@ async fn show_provide_progress_with_logging(
     // Therefore, a single completed request always indicates the end of the transfer.
     let min_required = 1;
 
-    if completed >= active && completed >= min_required && completed > 0 {
-        if !has_emitted_completed.swap(true, Ordering::SeqCst) {
-            emit_event(&app_handle, "transfer-completed");
-        }
+    if completed >= active
+        && completed >= min_required
+        && completed > 0
+        && !has_emitted_completed.swap(true, Ordering::SeqCst)
+    {
+        emit(&app_handle, TransferEvent::TransferCompleted);
     }
 
     Ok(())
@@ -883,38 +1625,375 @@ fn dedup_name(name: &str, seen: &mut std::collections::BTreeMap<String, usize>)
     }
 }
 
-/// Recursively collect files from a directory
-///
-/// - Returns a vac of (relative_path, absolute_path) tuples
-fn collect_path_files(path: &Path, root_name: &str) -> anyhow::Result<Vec<(String, PathBuf)>> {
+/// Which entries below a shared directory become part of the share, see [`SendOptions`].
+#[derive(Debug, Clone, Default)]
+struct CollectOptions {
+    symlink_policy: SymlinkPolicy,
+    use_ignore_files: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+}
+
+impl From<&SendOptions> for CollectOptions {
+    fn from(options: &SendOptions) -> Self {
+        Self {
+            symlink_policy: options.symlink_policy,
+            use_ignore_files: options.use_ignore_files,
+            include: options.include.clone(),
+            exclude: options.exclude.clone(),
+        }
+    }
+}
+
+/// # Description
+/// Total size of the files a share of `paths` with these options would contain, for previews
+/// that should announce the same size as the share.
+pub async fn shared_size(paths: &[PathBuf], options: &SendOptions) -> anyhow::Result<u64> {
+    let paths = paths.to_vec();
+    let collect_options = CollectOptions::from(options);
+    tokio::task::spawn_blocking(move || {
+        let mut total = 0u64;
+        for path in paths {
+            let path = path
+                .canonicalize()
+                .with_context(|| format!("failed to canonicalize {}", path.display()))?;
+            let collected = collect_path_files(&path, "item", &collect_options)?;
+            for (_, file) in collected.files {
+                total = total.saturating_add(std::fs::metadata(&file)?.len());
+            }
+        }
+        anyhow::Ok(total)
+    })
+    .await?
+}
+
+/// Files and directories found below a shared path, as (relative_path, absolute_path) tuples.
+#[derive(Debug, Default)]
+struct CollectedPaths {
+    files: Vec<(String, PathBuf)>,
+    /// Includes the shared directory itself, and directories without any file below them.
+    dirs: Vec<(String, PathBuf)>,
+    /// Links kept by `SymlinkPolicy::Preserve`, as (relative_path, target) tuples.
+    links: Vec<(String, String)>,
+    skipped_links: Vec<SkippedLink>,
+    skipped: Vec<SkippedEntry>,
+}
+
+/// Recursively collect files and directories from a directory
+fn collect_path_files(
+    path: &Path,
+    root_name: &str,
+    options: &CollectOptions,
+) -> anyhow::Result<CollectedPaths> {
     if path.is_file() {
         let rel = canonicalized_path_to_string(PathBuf::from(root_name), true)?;
-        return Ok(vec![(rel, path.to_path_buf())]);
+        return Ok(CollectedPaths {
+            files: vec![(rel, path.to_path_buf())],
+            ..Default::default()
+        });
     }
 
     if path.is_dir() {
-        let mut out = Vec::new();
-        for (index, entry) in WalkDir::new(path).into_iter().enumerate() {
+        let relative_name = |entry_path: &Path| -> anyhow::Result<String> {
+            let rel = entry_path
+                .strip_prefix(path)
+                .context("strip_prefix failed")?;
+            let mut prefixed = PathBuf::from(root_name);
+            prefixed.push(rel);
+            canonicalized_path_to_string(prefixed, true)
+        };
+        // For reporting entries whose name cannot be used in the collection.
+        let display_name = |entry_path: &Path| -> String {
+            let rel = entry_path.strip_prefix(path).unwrap_or(entry_path);
+            let mut name = root_name.to_string();
+            for component in rel.components() {
+                name.push('/');
+                name.push_str(&component.as_os_str().to_string_lossy());
+            }
+            name
+        };
+        let follow = options.symlink_policy == SymlinkPolicy::Follow;
+
+        let mut overrides = OverrideBuilder::new(path);
+        for glob in &options.include {
+            overrides
+                .add(glob)
+                .with_context(|| format!("invalid include glob {glob:?}"))?;
+        }
+        for glob in &options.exclude {
+            overrides
+                .add(&format!("!{glob}"))
+                .with_context(|| format!("invalid exclude glob {glob:?}"))?;
+        }
+
+        let mut walker = WalkBuilder::new(path);
+        walker
+            .standard_filters(false)
+            .follow_links(follow)
+            .overrides(overrides.build()?);
+        if options.use_ignore_files {
+            walker
+                .git_ignore(true)
+                .require_git(false)
+                .add_custom_ignore_filename(SENDME_IGNORE_FILE);
+        }
+
+        // Followed links are only walked if they resolve to somewhere inside the shared directory.
+        let escaping = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let escaping2 = escaping.clone();
+        let root = path.to_path_buf();
+        let skip_git_dirs = options.use_ignore_files;
+        walker.filter_entry(move |entry| {
+            if skip_git_dirs && entry.file_name() == ".git" {
+                return false;
+            }
+            if !entry.path_is_symlink() || !follow {
+                return true;
+            }
+            match entry.path().canonicalize() {
+                Ok(target) if target.starts_with(&root) => true,
+                _ => {
+                    escaping2.lock().unwrap().push(entry.path().to_path_buf());
+                    false
+                }
+            }
+        });
+
+        let mut out = CollectedPaths::default();
+        for (index, entry) in walker.build().enumerate() {
             let entry = match entry {
                 Ok(v) => v,
+                Err(e) => {
+                    match link_error(&e) {
+                        Some((link, reason)) => out.skipped_links.push(SkippedLink {
+                            name: display_name(&link),
+                            reason,
+                        }),
+                        None => {
+                            tracing::warn!("skipping inaccessible entry {}", index);
+                            out.skipped.push(SkippedEntry {
+                                name: error_path(&e)
+                                    .map_or_else(|| root_name.to_string(), display_name),
+                                reason: EntrySkipReason::Unreadable,
+                            });
+                        }
+                    }
+                    continue;
+                }
+            };
+            let Some(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = match relative_name(entry.path()) {
+                Ok(name) => name,
                 Err(_e) => {
-                    tracing::warn!("skipping inaccessible entry {}", index);
+                    tracing::warn!("skipping entry {} with an invalid name", index);
+                    out.skipped.push(SkippedEntry {
+                        name: display_name(entry.path()),
+                        reason: EntrySkipReason::InvalidName,
+                    });
                     continue;
                 }
             };
-            if !entry.file_type().is_file() {
+            if file_type.is_symlink() {
+                let reason = match options.symlink_policy {
+                    SymlinkPolicy::Preserve => {
+                        let target = std::fs::read_link(entry.path())
+                            .ok()
+                            .and_then(|target| link_target_string(&target))
+                            .and_then(|target| link_target_within(&name, &target));
+                        match target {
+                            Some(target) => {
+                                out.links.push((name, target));
+                                continue;
+                            }
+                            None => LinkSkipReason::OutsideShare,
+                        }
+                    }
+                    // Followed links only show up as links themselves if they are broken.
+                    SymlinkPolicy::Follow => LinkSkipReason::Broken,
+                    SymlinkPolicy::Skip => LinkSkipReason::Policy,
+                };
+                out.skipped_links.push(SkippedLink { name, reason });
                 continue;
             }
-            let file = entry.path().to_path_buf();
-            let rel = file
-                .strip_prefix(path)
-                .with_context(|| format!("strip_prefix failed for file {}", index))?;
-            let mut prefixed = PathBuf::from(root_name);
-            prefixed.push(rel);
-            let safe = canonicalized_path_to_string(prefixed, true)?;
-            out.push((safe, file));
+            if file_type.is_dir() {
+                out.dirs.push((name, entry.into_path()));
+            } else if file_type.is_file() {
+                out.files.push((name, entry.into_path()));
+            } else {
+                out.skipped.push(SkippedEntry {
+                    name,
+                    reason: EntrySkipReason::Unsupported,
+                });
+            }
+        }
+        for link in escaping.lock().unwrap().drain(..) {
+            out.skipped_links.push(SkippedLink {
+                name: display_name(&link),
+                reason: LinkSkipReason::OutsideShare,
+            });
         }
         return Ok(out);
     }
     anyhow::bail!("path is neither file nor directory");
 }
+
+/// The link that caused a walk error, and why it was skipped. `None` for other errors.
+fn link_error(error: &ignore::Error) -> Option<(PathBuf, LinkSkipReason)> {
+    match error {
+        ignore::Error::Loop { child, .. } => Some((child.clone(), LinkSkipReason::Loop)),
+        ignore::Error::WithPath { path, err } => link_error(err).or_else(|| {
+            path.is_symlink()
+                .then(|| (path.clone(), LinkSkipReason::Broken))
+        }),
+        ignore::Error::WithDepth { err, .. } => link_error(err),
+        _ => None,
+    }
+}
+
+/// The path a walk error is about, if it names one.
+fn error_path(error: &ignore::Error) -> Option<&Path> {
+    match error {
+        ignore::Error::WithPath { path, .. } => Some(path),
+        ignore::Error::WithDepth { err, .. } | ignore::Error::WithLineNumber { err, .. } => {
+            error_path(err)
+        }
+        _ => None,
+    }
+}
+
+/// A relative link target with `/` separators, or `None` for absolute or non-UTF-8 targets.
+fn link_target_string(target: &Path) -> Option<String> {
+    let parts = target
+        .components()
+        .map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            Component::ParentDir => Some(".."),
+            Component::CurDir => Some("."),
+            Component::RootDir | Component::Prefix(_) => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[cfg(unix)]
+    #[test]
+    fn canonicalized_path_rejects_backslash() {
+        let path = Path::new("system-systemd\\x2dcryptsetup.slice");
+        assert!(canonicalized_path_to_string(path, true).is_err());
+    }
+
+    #[test]
+    fn canonicalized_path_accepts_normal() {
+        let result = canonicalized_path_to_string(Path::new("subdir/file.txt"), true);
+        assert_eq!(result.unwrap(), "subdir/file.txt");
+    }
+
+    #[test]
+    fn canonicalized_path_rejects_parent_traversal() {
+        assert!(canonicalized_path_to_string(Path::new("../etc/passwd"), true).is_err());
+    }
+
+    #[test]
+    fn canonicalized_path_rejects_absolute_when_relative() {
+        assert!(canonicalized_path_to_string(Path::new("/etc/passwd"), true).is_err());
+    }
+
+    #[test]
+    fn only_requests_for_files_count_as_downloads() {
+        use iroh_blobs::protocol::ChunkRanges;
+
+        let header = ChunkRangesSeq::from_ranges([ChunkRanges::all(), ChunkRanges::all()]);
+        let second_file = ChunkRangesSeq::from_ranges([
+            ChunkRanges::all(),
+            ChunkRanges::all(),
+            ChunkRanges::empty(),
+            ChunkRanges::all(),
+        ]);
+        assert!(!fetches_file_data(&header));
+        assert!(!fetches_file_data(&ChunkRangesSeq::verified_child_sizes()));
+        assert!(fetches_file_data(&second_file));
+        assert!(fetches_file_data(&ChunkRangesSeq::all()));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn import_skips_invalid_files() {
+        use tempfile::TempDir;
+
+        let td = TempDir::new().unwrap();
+        let dir = td.path().join("testdir");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("good.txt"), "hello").unwrap();
+        std::fs::write(dir.join(format!("bad{}file.txt", '\\')), "bad").unwrap();
+
+        let path = dir.canonicalize().unwrap();
+        let collected = collect_path_files(&path, "testdir", &CollectOptions::default()).unwrap();
+
+        assert_eq!(collected.files.len(), 1, "should skip file with backslash");
+        assert!(collected.files[0].0.contains("good.txt"));
+        assert_eq!(collected.skipped.len(), 1);
+        assert_eq!(collected.skipped[0].reason, EntrySkipReason::InvalidName);
+    }
+
+    #[tokio::test]
+    async fn imported_size_matches_shared_size_with_ignore_files() {
+        use iroh_blobs::store::mem::MemStore;
+        use tempfile::TempDir;
+
+        let td = TempDir::new().unwrap();
+        let dir = td.path().join("project");
+        for (name, content) in [
+            (".gitignore", "target/\n*.log\n"),
+            (".sendmeignore", "secret.txt\n"),
+            ("src/main.rs", "fn main() {}"),
+            ("src/debug.log", "log"),
+            ("target/out.bin", "binary"),
+            ("secret.txt", "secret"),
+            (".git/HEAD", "ref: refs/heads/main\n"),
+        ] {
+            let file = dir.join(name);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, content).unwrap();
+        }
+        let options = SendOptions {
+            use_ignore_files: true,
+            ..Default::default()
+        };
+        let paths = vec![dir.canonicalize().unwrap()];
+
+        let announced = shared_size(&paths, &options).await.unwrap();
+        let store = MemStore::new();
+        let imported = import_paths(
+            paths,
+            &store,
+            false,
+            false,
+            &CollectOptions::from(&options),
+            &mut ImportCache::default(),
+        )
+        .await
+        .unwrap();
+
+        let names: Vec<&str> = imported
+            .collection
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            [
+                "project/.gitignore",
+                "project/.sendmeignore",
+                "project/src/main.rs"
+            ]
+        );
+        assert_eq!(announced, imported.size);
+    }
+}
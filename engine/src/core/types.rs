@@ -1,11 +1,17 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+use super::contacts::AddressBook;
+use super::control::ReceiveControl;
+use super::decisions::PendingDecisions;
+use super::events::TransferEvent;
+use super::ratelimit::{RateLimits, ShareRateLimiter};
+use super::receivers::ShareReceivers;
 
-// Import the EventEmitter trait - we'll define it here or import it
 pub trait EventEmitter: Send + Sync {
-    fn emit_event(&self, event_name: &str) -> Result<(), String>;
-    fn emit_event_with_payload(&self, event_name: &str, payload: &str) -> Result<(), String>;
+    fn emit(&self, event: &TransferEvent) -> Result<(), String>;
 }
 
 // Type alias for the app handle - we use Arc<dyn EventEmitter> to allow cloning and avoid direct tauri dependency in core
@@ -16,19 +22,291 @@ pub struct SendResult {
     pub hash: String,
     pub size: u64,
     pub entry_type: String, // "file" or "directory"
+    pub file_count: u64,
+    /// Symlinks below the shared paths that are not part of the share.
+    pub skipped_links: Vec<SkippedLink>,
+    /// Files and directories below the shared paths that could not be shared.
+    pub skipped: Vec<SkippedEntry>,
+    /// Who is downloading the share and how far they got.
+    pub receivers: ShareReceivers,
+    /// Changes the upload rate limits of the running share.
+    pub rate_limiter: ShareRateLimiter,
 
     // CRITICAL: These fields must be kept alive for the duration of the share
     pub router: iroh::protocol::Router, // Keeps the server running and protocols active
     pub temp_tag: iroh_blobs::api::TempTag, // Prevents data from being garbage collected
     pub blobs_data_dir: PathBuf,        // Path for cleanup when share stops
     pub _progress_handle: n0_future::task::AbortOnDropHandle<anyhow::Result<()>>, // Keeps event channel open
-    pub _store: iroh_blobs::store::fs::FsStore, // Keeps the blob storage alive
+    pub _throttle_handle: n0_future::task::AbortOnDropHandle<()>, // Paces rate limited connections
+    pub _watch_handle: Option<n0_future::task::AbortOnDropHandle<()>>, // Rescans a live share
+    pub _limits_handle: Option<n0_future::task::AbortOnDropHandle<()>>, // Stops an expiring share
+    pub _store: iroh_blobs::store::fs::FsStore,                   // Keeps the blob storage alive
+}
+
+impl SendResult {
+    /// The serializable part of the result, for the UI, the CLI and logs.
+    pub fn info(&self) -> ShareInfo {
+        ShareInfo {
+            ticket: self.ticket.clone(),
+            hash: self.hash.clone(),
+            size: self.size,
+            entry_type: self.entry_type.clone(),
+            file_count: self.file_count,
+            endpoint_id: self.router.endpoint().id().to_string(),
+            skipped_links: self.skipped_links.clone(),
+            skipped: self.skipped.clone(),
+        }
+    }
+}
+
+/// # Description
+/// Describes a running share without the handles that keep it alive.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub ticket: String,
+    pub hash: String,
+    pub size: u64,
+    pub entry_type: String,
+    pub file_count: u64,
+    /// Endpoint ID the share presents to receivers, i.e. the one their address books and
+    /// `SendOptions::allowed_receivers` of other devices know it by.
+    #[serde(default)]
+    pub endpoint_id: String,
+    #[serde(default)]
+    pub skipped_links: Vec<SkippedLink>,
+    #[serde(default)]
+    pub skipped: Vec<SkippedEntry>,
+}
+
+/// A symlink that was left out of a share, see [`SymlinkPolicy`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedLink {
+    /// Name the link would have had in the collection.
+    pub name: String,
+    pub reason: LinkSkipReason,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkSkipReason {
+    /// `SymlinkPolicy::Skip` is in effect.
+    Policy,
+    /// The link points to one of its own parent directories.
+    Loop,
+    /// The link target does not exist.
+    Broken,
+    /// The link points outside of the shared directory.
+    OutsideShare,
+}
+
+/// A file or directory that could not be shared. Entries left out on purpose, by ignore rules
+/// or globs, are not listed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedEntry {
+    /// Name the entry would have had in the collection, or the shared path itself.
+    pub name: String,
+    pub reason: EntrySkipReason,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntrySkipReason {
+    /// The entry could not be listed or read, e.g. for lack of permissions.
+    Unreadable,
+    /// The name cannot be used as a collection entry, e.g. it contains a backslash.
+    InvalidName,
+    /// Neither a file nor a directory, e.g. a socket or a device.
+    Unsupported,
+    /// A shared path without any file to share below it.
+    NoFiles,
+}
+
+/// # Description
+/// Outcome of a receive. Serializable so the app, the CLI and logs can render it the same way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct ReceiveResult {
+    /// One-line human readable summary, see [`ReceiveResult::summary`].
     pub message: String,
+    /// Directory the collection was exported into.
     pub file_path: PathBuf,
+    /// Address book name of the sender, if known.
+    pub sender_name: Option<String>,
+    /// Number of entries in the collection.
+    pub total_files: u64,
+    /// Bytes of file data, excluding the collection metadata blob.
+    pub payload_size: u64,
+    /// Blob bytes fetched from the sender by this call, including the collection metadata blob.
+    /// Only the missing part is counted when resuming; zero when everything was cached.
+    pub bytes_fetched: u64,
+    /// Wall time from start to finish, including connecting and exporting.
+    pub duration_ms: u64,
+    /// Average speed of the fetch phase in bytes per second.
+    pub average_speed_bps: f64,
+    /// Targets that already existed, and how each was handled.
+    pub conflicts: Vec<ExportConflict>,
+    /// Where each collection entry was written. Entries whose existing target was kept are left
+    /// out; they are listed in `conflicts`.
+    pub files: Vec<ReceivedFile>,
+    pub connection_path: ConnectionPath,
+    /// True if all data was already in the local store and nothing was fetched from the sender.
+    pub was_cached: bool,
+    /// What a mirror receive changed in the output directory, see [`ReceiveOptions::mirror`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<MirrorDiff>,
+}
+
+impl ReceiveResult {
+    pub fn summary(&self) -> String {
+        let verb = if self.mirror.is_some() {
+            "Mirrored"
+        } else {
+            "Downloaded"
+        };
+        let mut message = format!(
+            "{} {} files, {} bytes",
+            verb, self.total_files, self.payload_size
+        );
+        if let Some(name) = &self.sender_name {
+            message.push_str(&format!(" from {}", name));
+        }
+        // Overwrites are expected when mirroring, the diff says what changed.
+        if let Some(diff) = &self.mirror {
+            message.push_str(&format!(
+                " ({} added, {} updated, {} unchanged, {} deleted)",
+                diff.added.len(),
+                diff.updated.len(),
+                diff.unchanged.len(),
+                diff.deleted.len()
+            ));
+            return message;
+        }
+        let counts: Vec<String> = [
+            (ConflictAction::Renamed, "renamed"),
+            (ConflictAction::Overwritten, "overwritten"),
+            (ConflictAction::Skipped, "skipped"),
+            (ConflictAction::Identical, "already up to date"),
+        ]
+        .iter()
+        .filter_map(|(action, label)| {
+            let count = self
+                .conflicts
+                .iter()
+                .filter(|c| c.action == *action)
+                .count();
+            (count > 0).then(|| format!("{count} {label}"))
+        })
+        .collect();
+        if !counts.is_empty() {
+            message.push_str(&format!(" ({})", counts.join(", ")));
+        }
+        message
+    }
+}
+
+/// A collection entry and the path it was exported to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedFile {
+    pub name: String,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// # Description
+/// Difference between the output directory and the collection found by a mirror receive.
+/// All lists hold entry names, i.e. paths relative to the output directory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorDiff {
+    /// Entries that did not exist locally.
+    pub added: Vec<String>,
+    /// Entries whose local file had different content and was replaced.
+    pub updated: Vec<String>,
+    /// Entries whose local file already had the same BLAKE3 hash; these were not fetched.
+    pub unchanged: Vec<String>,
+    /// Local files that are not in the collection and were deleted.
+    pub deleted: Vec<String>,
+}
+
+/// Network path the data was fetched over, as selected when the fetch finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionPath {
+    /// Direct (holepunched or LAN) connection.
+    Direct,
+    /// Traffic went through a relay server.
+    Relay,
+    /// Nothing was fetched, the data was already in the local store.
+    Local,
+    /// The connection had no selected path when it was inspected.
+    Unknown,
+}
+
+impl ConnectionPath {
+    pub fn selected(connection: &iroh::endpoint::Connection) -> Self {
+        let paths = connection.paths();
+        match paths.iter().find(|path| path.is_selected()) {
+            Some(path) if path.is_relay() => Self::Relay,
+            Some(path) if path.is_ip() => Self::Direct,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+/// An export target that already existed, and what was done about it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportConflict {
+    pub original: String,
+    /// Path the entry was written to. Same as `original` unless it was renamed.
+    pub resolved: String,
+    pub action: ConflictAction,
+}
+
+/// How an existing export target was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictAction {
+    /// The entry was written next to it as `name (1).ext`.
+    Renamed,
+    Overwritten,
+    /// The existing file was kept and the entry was not exported.
+    Skipped,
+    /// The existing file already has the entry's content and was kept.
+    Identical,
+}
+
+/// # Description
+/// What `download` does when an export target already exists.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictPolicy {
+    /// Export as `name (1).ext` next to the existing file.
+    #[default]
+    Rename,
+    Overwrite,
+    /// Keep the existing file if its BLAKE3 hash matches the entry, otherwise rename.
+    SkipIfIdentical,
+    /// Always keep the existing file.
+    Skip,
+    /// Emit `export-conflict` and wait for a [`ConflictDecision`] through
+    /// `ReceiveOptions::conflict_decisions`. Without an event handler this behaves like `Rename`.
+    Ask,
+}
+
+/// The app's answer to an `export-conflict` event.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConflictDecision {
+    /// `Ask` is treated as `Rename`.
+    pub policy: ConflictPolicy,
+    /// Use the same answer for the remaining conflicts of this receive.
+    #[serde(default)]
+    pub apply_to_all: bool,
 }
 
 #[derive(Debug, Default)]
@@ -37,6 +315,106 @@ pub struct SendOptions {
     pub ticket_type: AddrInfoOptions,
     pub magic_ipv4_addr: Option<std::net::SocketAddrV4>,
     pub magic_ipv6_addr: Option<std::net::SocketAddrV6>,
+    /// Cancels share setup (import and relay connection). Once the share is running, stop it via its router.
+    pub cancel_token: CancellationToken,
+    /// Endpoint key, e.g. from an `IdentityLease`. Falls back to `get_or_create_secret` when unset.
+    pub secret_key: Option<iroh::SecretKey>,
+    /// Ship permissions, modification times and empty directories in an attributes manifest.
+    /// Receivers older than the manifest export it as a plain `.sendme-attributes.json` file.
+    pub preserve_attributes: bool,
+    /// What to do with symlinks found below a shared directory.
+    pub symlink_policy: SymlinkPolicy,
+    /// Leave out what `.gitignore` and `.sendmeignore` files inside a shared directory ignore,
+    /// and `.git` directories. Ignore files above the shared directory are not read.
+    pub use_ignore_files: bool,
+    /// Globs in `.gitignore` syntax, relative to each shared directory. If not empty, only
+    /// matching files are shared. A match also wins over ignore files, but not for files below
+    /// a directory they ignore.
+    pub include: Vec<String>,
+    /// Globs in `.gitignore` syntax, relative to each shared directory, of files and
+    /// directories to leave out.
+    pub exclude: Vec<String>,
+    /// Fail the share if any file or directory would be skipped, see
+    /// `SendResult::skipped`. Skipped symlinks do not count.
+    pub strict: bool,
+    /// Make a live share: the shared paths are rescanned at this interval and changes are
+    /// published as a new version under the same ticket. Receivers with
+    /// `ReceiveOptions::latest` set get the current version instead of the first one.
+    pub watch_interval: Option<std::time::Duration>,
+    /// Stop the share this long after it started, emitting `share-expired`.
+    pub expires_after: Option<std::time::Duration>,
+    /// Stop the share once this many downloads completed, emitting `share-expired`. A download
+    /// is a completed request for file data; receives that were cancelled do not count.
+    pub max_downloads: Option<u64>,
+    /// Only serve the share, its metadata included, to receivers that know this passphrase.
+    pub password: Option<String>,
+    /// Only serve the share, its metadata included, to these receivers. Connections from other
+    /// endpoints are refused and reported as `connection-rejected`.
+    pub allowed_receivers: Option<std::collections::HashSet<iroh::EndpointId>>,
+    /// Ask the app about every new receiver with a `connection-request` event and only serve
+    /// it once accepted through `connection_decisions`. Requests that are not answered within
+    /// this time are denied. Without an event handler every receiver is denied. Until then
+    /// the metadata only tells how much is shared, not what.
+    pub confirm_receivers: Option<std::time::Duration>,
+    /// Where answers to `connection-request` events arrive, `true` accepting the receiver.
+    pub connection_decisions: PendingDecisions<bool>,
+    /// Known peers, used to name receivers in `connection-request` events.
+    pub contacts: Option<AddressBook>,
+    /// Upload rate limits, adjustable later through `SendResult::rate_limiter`.
+    pub rate_limits: RateLimits,
+}
+
+/// Why a share stopped by itself, see [`SendOptions::expires_after`] and
+/// [`SendOptions::max_downloads`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareExpiryReason {
+    TimeLimit,
+    DownloadLimit,
+}
+
+/// # Description
+/// Why a sender refused a receiver. `download` and `fetch_metadata` fail with this error inside
+/// their `anyhow::Error`, so callers can tell it apart with `downcast_ref`, e.g. to ask for a
+/// passphrase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShareAccessError {
+    /// The share is protected and no passphrase was given.
+    PasswordRequired,
+    /// The sender did not accept the given passphrase.
+    WrongPassword,
+    /// The receiver is not in `SendOptions::allowed_receivers`.
+    NotAllowed,
+}
+
+impl std::fmt::Display for ShareAccessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShareAccessError::PasswordRequired => write!(f, "password required"),
+            ShareAccessError::WrongPassword => write!(f, "wrong password"),
+            ShareAccessError::NotAllowed => write!(f, "the sender did not allow this receiver"),
+        }
+    }
+}
+
+impl std::error::Error for ShareAccessError {}
+
+/// # Description
+/// How symlinks below a shared directory are handled. Shared paths themselves are always
+/// resolved. Links that are left out are listed in `SendResult::skipped_links`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SymlinkPolicy {
+    #[default]
+    Skip,
+    /// Share the link target under the link's name. Links pointing outside of the shared
+    /// directory and link loops are skipped.
+    Follow,
+    /// Share the link itself, recreated by the receiver. Only relative links that stay inside
+    /// the shared directory are kept. Needs the attributes manifest, which is sent for these
+    /// links even without `preserve_attributes`.
+    Preserve,
 }
 
 #[derive(Debug, Default)]
@@ -45,11 +423,60 @@ pub struct ReceiveOptions {
     pub relay_mode: RelayModeOption,
     pub magic_ipv4_addr: Option<std::net::SocketAddrV4>,
     pub magic_ipv6_addr: Option<std::net::SocketAddrV6>,
+    /// Cancels the download; partial data is kept for `resume_download`.
+    pub cancel_token: CancellationToken,
+    /// Pauses, resumes and caps the rate of the download while it runs.
+    pub control: ReceiveControl,
+    /// Known peers, used to report who a receive is from.
+    pub contacts: Option<AddressBook>,
+    /// Endpoint key, e.g. from an `IdentityLease`. Falls back to `get_or_create_secret` when unset.
+    pub secret_key: Option<iroh::SecretKey>,
+    /// What to do with export targets that already exist.
+    pub conflict_policy: ConflictPolicy,
+    /// Where answers to `export-conflict` events arrive in `ConflictPolicy::Ask` mode.
+    pub conflict_decisions: PendingDecisions<ConflictDecision>,
+    /// Only fetch and export the entries matching any of these. Empty receives everything.
+    pub selection: Vec<EntrySelector>,
+    /// Update an earlier copy of the share in the output directory instead of adding a new one.
+    /// Entries whose local file already matches are neither fetched nor exported, changed files
+    /// are overwritten regardless of `conflict_policy`.
+    pub mirror: Option<MirrorOptions>,
+    /// Ask the sender for the current version of a live share and receive that instead of the
+    /// version the ticket was created for.
+    pub latest: bool,
+    /// Passphrase of a protected share, see `SendOptions::password`. Never written to the
+    /// resume record.
+    pub password: Option<String>,
+}
+
+/// # Description
+/// Settings of a mirror receive, see [`ReceiveOptions::mirror`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorOptions {
+    /// Delete local files below the shared directories that are not in the collection.
+    /// Files outside of the shared directories are never touched.
+    #[serde(default)]
+    pub delete_extraneous: bool,
 }
 
-#[derive(Clone, Debug)]
+/// # Description
+/// Picks collection entries for a selective receive.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntrySelector {
+    /// An entry name such as `logs/app.log`. A directory name selects everything below it.
+    Name(String),
+    /// A glob over entry names, e.g. `logs/*.log`. `*` does not cross `/`.
+    Glob(String),
+    /// Position of the entry in the collection.
+    Index(usize),
+}
+
+#[derive(Clone, Debug, Default)]
 pub enum RelayModeOption {
     Disabled,
+    #[default]
     Default,
     Custom {
         urls: Vec<iroh::RelayUrl>,
@@ -57,12 +484,6 @@ pub enum RelayModeOption {
     },
 }
 
-impl Default for RelayModeOption {
-    fn default() -> Self {
-        Self::Default
-    }
-}
-
 impl From<RelayModeOption> for iroh::endpoint::RelayMode {
     fn from(value: RelayModeOption) -> Self {
         match value {
@@ -80,6 +501,22 @@ impl From<RelayModeOption> for iroh::endpoint::RelayMode {
     }
 }
 
+/// Parses `disabled`, `default` or a single custom relay URL, as accepted by the `--relay` flag.
+impl FromStr for RelayModeOption {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "disabled" => Ok(Self::Disabled),
+            "default" => Ok(Self::Default),
+            _ => Ok(Self::Custom {
+                urls: vec![iroh::RelayUrl::from_str(s)?],
+                auth_token: None,
+            }),
+        }
+    }
+}
+
 #[cfg(test)]
 mod relay_mode_tests {
     use super::*;
@@ -106,6 +543,23 @@ mod relay_mode_tests {
         let relay_mode: iroh::endpoint::RelayMode = mode.into();
         assert!(matches!(relay_mode, iroh::endpoint::RelayMode::Custom(_)));
     }
+
+    #[test]
+    fn relay_mode_from_str() {
+        assert!(matches!(
+            RelayModeOption::from_str("disabled").unwrap(),
+            RelayModeOption::Disabled
+        ));
+        assert!(matches!(
+            RelayModeOption::from_str("default").unwrap(),
+            RelayModeOption::Default
+        ));
+        assert!(matches!(
+            RelayModeOption::from_str("https://relay.example.com").unwrap(),
+            RelayModeOption::Custom { urls, auth_token: None } if urls.len() == 1
+        ));
+        assert!(RelayModeOption::from_str("not a url").is_err());
+    }
 }
 
 /// # Description
@@ -132,6 +586,13 @@ pub struct FileMetadata {
     pub mime_type: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub items: Option<Vec<FilePreviewItem>>,
+    /// Display name of the sender from the receiver's address book. Filled in on the receive
+    /// side only; a value announced by the sender is discarded because it cannot be verified.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sender_name: Option<String>,
+    /// Set by live shares, which receivers should get with `ReceiveOptions::latest`.
+    #[serde(default)]
+    pub live: bool,
 }
 
 #[derive(
@@ -1,11 +1,24 @@
 pub mod core;
 
+pub use tokio_util::sync::CancellationToken;
+
 pub use core::{
-    receive::{download, fetch_metadata},
+    contacts::{AddressBook, Contact},
+    control::ReceiveControl,
+    decisions::{PendingDecision, PendingDecisions},
+    events::TransferEvent,
+    identity::{Identity, IdentityLease},
+    ratelimit::{RateLimits, ShareRateLimiter},
+    receive::{download, fetch_metadata, resume_download},
+    receivers::{ReceiverProgress, ReceiverStatus, ShareReceivers},
+    resume::{discard_interrupted_download, list_interrupted_downloads, InterruptedDownload},
     send::start_share,
-    send::start_share_items,
+    send::{shared_size, start_share_items},
     types::{
-        AddrInfoOptions, AppHandle, EventEmitter, FileMetadata, FilePreviewItem, ReceiveOptions,
-        ReceiveResult, RelayModeOption, SendOptions, SendResult,
+        AddrInfoOptions, AppHandle, ConflictAction, ConflictDecision, ConflictPolicy,
+        ConnectionPath, EntrySelector, EntrySkipReason, EventEmitter, ExportConflict, FileMetadata,
+        FilePreviewItem, LinkSkipReason, MirrorDiff, MirrorOptions, ReceiveOptions, ReceiveResult,
+        ReceivedFile, RelayModeOption, SendOptions, SendResult, ShareAccessError,
+        ShareExpiryReason, ShareInfo, SkippedEntry, SkippedLink, SymlinkPolicy,
     },
 };
@@ -0,0 +1,883 @@
+//! Headless `sendme` command line tool built on the engine crate.
+//!
+//! Uses the same send/receive code paths as the desktop app, so tickets are interchangeable
+//! between the two.
+
+use std::{
+    collections::HashSet,
+    net::{SocketAddrV4, SocketAddrV6},
+    path::PathBuf,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Context;
+use clap::{
+    error::{ContextKind, ErrorKind},
+    CommandFactory, Parser, Subcommand,
+};
+use console::style;
+use engine::{
+    download, fetch_metadata, start_share_items, AddrInfoOptions, AddressBook, AppHandle,
+    CancellationToken, ConflictAction, ConflictDecision, ConflictPolicy, EntrySelector,
+    EventEmitter, FileMetadata, Identity, MirrorOptions, PendingDecisions, RateLimits,
+    ReceiveControl, ReceiveOptions, ReceiverStatus, RelayModeOption, SendOptions,
+    ShareExpiryReason, SymlinkPolicy, TransferEvent,
+};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+use iroh::{EndpointId, SecretKey};
+
+/// Send a file or directory between two machines, using blake3 verified streaming.
+///
+/// For all subcommands, you can specify a secret key using the IROH_SECRET
+/// environment variable or a key file via --identity. If you don't, a random one will be
+/// generated.
+#[derive(Parser, Debug)]
+#[command(name = "sendme", version, about)]
+pub struct Args {
+    #[clap(subcommand)]
+    pub command: Commands,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Commands {
+    /// Send one or more files or directories.
+    Send(SendArgs),
+
+    /// Receive a file or directory.
+    #[clap(visible_alias = "recv")]
+    Receive(ReceiveArgs),
+
+    /// Show what a ticket points to without downloading it.
+    Metadata(MetadataArgs),
+}
+
+#[derive(Parser, Debug)]
+pub struct CommonArgs {
+    /// The IPv4 address that magicsocket will listen on.
+    ///
+    /// If None, defaults to a random free port, but it can be useful to specify a fixed
+    /// port, e.g. to configure a firewall rule.
+    #[clap(long, default_value = None)]
+    pub magic_ipv4_addr: Option<SocketAddrV4>,
+
+    /// The IPv6 address that magicsocket will listen on.
+    ///
+    /// If None, defaults to a random free port, but it can be useful to specify a fixed
+    /// port, e.g. to configure a firewall rule.
+    #[clap(long, default_value = None)]
+    pub magic_ipv6_addr: Option<SocketAddrV6>,
+
+    /// Suppress progress bars.
+    #[clap(long, default_value_t = false)]
+    pub no_progress: bool,
+
+    /// The relay URL to use as a home relay,
+    ///
+    /// Can be set to "disabled" to disable relay servers and "default"
+    /// to configure default servers.
+    #[clap(long, default_value = "default")]
+    pub relay: RelayModeOption,
+
+    /// Key file holding a persistent endpoint identity, created on first use.
+    ///
+    /// Without it the IROH_SECRET environment variable or a random key is used.
+    #[clap(long)]
+    pub identity: Option<PathBuf>,
+
+    /// Passphrase of the share.
+    ///
+    /// When sending, receivers must know it to see or fetch anything. Prefer the environment
+    /// variable, command lines are visible to other users.
+    #[clap(long, env = "SENDME_PASSWORD", hide_env_values = true)]
+    pub password: Option<String>,
+}
+
+impl CommonArgs {
+    fn secret_key(&self) -> anyhow::Result<Option<SecretKey>> {
+        let Some(path) = &self.identity else {
+            return Ok(None);
+        };
+        let identity = Identity::load_or_create(path)?;
+        eprintln!("using identity {}", identity.endpoint_id());
+        Ok(identity.try_lease().map(|lease| lease.secret_key().clone()))
+    }
+}
+
+#[derive(Parser, Debug)]
+pub struct SendArgs {
+    /// Paths to the files or directories to send.
+    ///
+    /// A single path is shared under its last component; several paths are shared
+    /// as one collection.
+    #[clap(required = true)]
+    pub paths: Vec<PathBuf>,
+
+    /// What type of ticket to use.
+    ///
+    /// Use "id" for the shortest type only including the endpoint ID,
+    /// "addresses" to only add IP addresses without a relay url,
+    /// "relay" to only add a relay address, and "relay-and-addresses"
+    /// to include both.
+    ///
+    /// Generally, the more information the higher the likelihood of
+    /// a successful connection, but also the bigger a ticket to connect.
+    #[clap(long, default_value = "relay-and-addresses", value_parser = parse_ticket_type)]
+    pub ticket_type: AddrInfoOptions,
+
+    /// Also send file permissions, modification times and empty directories.
+    #[clap(long)]
+    pub preserve_attributes: bool,
+
+    /// What to do with symlinks inside shared directories.
+    ///
+    /// One of skip, follow (share what they point to) or preserve (share the link itself).
+    #[clap(long, default_value = "skip", value_parser = parse_symlink_policy)]
+    pub symlinks: SymlinkPolicy,
+
+    /// Share everything in shared directories, ignoring .gitignore and .sendmeignore files.
+    #[clap(long)]
+    pub no_ignore: bool,
+
+    /// Only share files in shared directories matching this glob (.gitignore syntax).
+    /// Can be given several times.
+    #[clap(long = "include", value_name = "GLOB")]
+    pub include: Vec<String>,
+
+    /// Leave out files and directories matching this glob (.gitignore syntax).
+    /// Can be given several times.
+    #[clap(long = "exclude", value_name = "GLOB")]
+    pub exclude: Vec<String>,
+
+    /// Fail instead of sharing the rest if a file or directory cannot be read.
+    #[clap(long)]
+    pub strict: bool,
+
+    /// Keep rescanning the shared paths and publish changes under the same ticket.
+    ///
+    /// Takes the rescan interval in seconds, 2 if omitted. Receivers get the current
+    /// version with `receive --latest`.
+    #[clap(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "2")]
+    pub watch: Option<u64>,
+
+    /// Stop sharing after this long, e.g. 90s, 30m, 12h or 7d.
+    #[clap(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub expires_in: Option<Duration>,
+
+    /// Stop sharing after this many completed downloads.
+    #[clap(long, value_name = "COUNT")]
+    pub max_downloads: Option<u64>,
+
+    /// Only let this receiver fetch the share. Can be given several times.
+    ///
+    /// Takes an endpoint ID or the name of a contact in the address book.
+    #[clap(long = "allow", value_name = "RECEIVER")]
+    pub allow: Vec<String>,
+
+    /// Ask on the terminal before serving each new receiver.
+    ///
+    /// Takes how many seconds to wait for an answer before denying the receiver, 60 if
+    /// omitted.
+    #[clap(long, value_name = "SECONDS", num_args = 0..=1, default_missing_value = "60")]
+    pub confirm: Option<u64>,
+
+    /// Upload at most this many bytes per second to all receivers together, e.g. 500K or 2M.
+    #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+    pub max_rate: Option<u64>,
+
+    /// Upload at most this many bytes per second to each receiver, e.g. 500K or 2M.
+    #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+    pub max_rate_per_receiver: Option<u64>,
+
+    #[clap(flatten)]
+    pub common: CommonArgs,
+
+    /// Store the receive command in the clipboard.
+    #[cfg(feature = "clipboard")]
+    #[clap(short = 'c', long)]
+    pub clipboard: bool,
+}
+
+#[derive(Parser, Debug)]
+pub struct ReceiveArgs {
+    /// The ticket to use to connect to the sender.
+    pub ticket: String,
+
+    /// Directory to export the received data into.
+    ///
+    /// Defaults to the downloads directory, or the current directory if there is none.
+    #[clap(long, short = 'o')]
+    pub output_dir: Option<PathBuf>,
+
+    /// What to do when a file already exists in the output directory.
+    ///
+    /// One of rename, overwrite, skip-if-identical, skip or ask.
+    #[clap(long, default_value = "rename", value_parser = parse_conflict_policy)]
+    pub on_conflict: ConflictPolicy,
+
+    /// Only receive matching entries. Can be given several times.
+    ///
+    /// Takes an entry name (a directory name selects everything below it), a glob such as
+    /// `logs/*.log`, or the position of the entry in the shared collection.
+    #[clap(long, value_name = "ENTRY", value_parser = parse_entry_selector)]
+    pub only: Vec<EntrySelector>,
+
+    /// Update an earlier copy of the share in the output directory.
+    ///
+    /// Files whose content already matches are not fetched again, changed files are overwritten.
+    #[clap(long)]
+    pub mirror: bool,
+
+    /// With --mirror, delete local files in the shared directories that are no longer shared.
+    #[clap(long, requires = "mirror")]
+    pub delete: bool,
+
+    /// Receive the current version of a share started with `send --watch`.
+    #[clap(long)]
+    pub latest: bool,
+
+    /// Download at most this many bytes per second, e.g. 500K or 2M.
+    #[clap(long, value_name = "RATE", value_parser = parse_rate)]
+    pub max_rate: Option<u64>,
+
+    /// Print the result as JSON instead of a summary.
+    #[clap(long)]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub common: CommonArgs,
+}
+
+#[derive(Parser, Debug)]
+pub struct MetadataArgs {
+    /// The ticket to inspect.
+    pub ticket: String,
+
+    /// Print the metadata as JSON instead of a summary.
+    #[clap(long)]
+    pub json: bool,
+
+    #[clap(flatten)]
+    pub common: CommonArgs,
+}
+
+fn parse_ticket_type(s: &str) -> Result<AddrInfoOptions, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "id" => Ok(AddrInfoOptions::Id),
+        "relay" => Ok(AddrInfoOptions::Relay),
+        "addresses" => Ok(AddrInfoOptions::Addresses),
+        "relay-and-addresses" | "relayandaddresses" => Ok(AddrInfoOptions::RelayAndAddresses),
+        _ => Err(format!(
+            "invalid ticket type {s:?}, expected one of: id, relay, addresses, relay-and-addresses"
+        )),
+    }
+}
+
+fn parse_conflict_policy(s: &str) -> Result<ConflictPolicy, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "rename" => Ok(ConflictPolicy::Rename),
+        "overwrite" => Ok(ConflictPolicy::Overwrite),
+        "skip-if-identical" => Ok(ConflictPolicy::SkipIfIdentical),
+        "skip" => Ok(ConflictPolicy::Skip),
+        "ask" => Ok(ConflictPolicy::Ask),
+        _ => Err(format!(
+            "invalid conflict policy {s:?}, expected one of: rename, overwrite, skip-if-identical, skip, ask"
+        )),
+    }
+}
+
+fn parse_symlink_policy(s: &str) -> Result<SymlinkPolicy, String> {
+    match s.to_ascii_lowercase().as_str() {
+        "skip" => Ok(SymlinkPolicy::Skip),
+        "follow" => Ok(SymlinkPolicy::Follow),
+        "preserve" => Ok(SymlinkPolicy::Preserve),
+        _ => Err(format!(
+            "invalid symlink policy {s:?}, expected one of: skip, follow, preserve"
+        )),
+    }
+}
+
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid duration {s:?}, expected e.g. 90s, 30m, 12h or 7d"))?;
+    let seconds = match unit {
+        "" | "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => {
+            return Err(format!(
+                "invalid duration unit {unit:?}, expected s, m, h or d"
+            ))
+        }
+    };
+    Ok(Duration::from_secs(number.saturating_mul(seconds)))
+}
+
+/// Parses a rate in bytes per second, with an optional K, M or G suffix for powers of 1024.
+fn parse_rate(s: &str) -> Result<u64, String> {
+    let (number, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len()));
+    let number: u64 = number
+        .parse()
+        .map_err(|_| format!("invalid rate {s:?}, expected e.g. 500K or 2M"))?;
+    let bytes = match unit.to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1 << 10,
+        "M" | "MB" | "MIB" => 1 << 20,
+        "G" | "GB" | "GIB" => 1 << 30,
+        _ => return Err(format!("invalid rate unit {unit:?}, expected K, M or G")),
+    };
+    match number.saturating_mul(bytes) {
+        0 => Err("rate must be greater than zero".to_string()),
+        rate => Ok(rate),
+    }
+}
+
+fn parse_entry_selector(s: &str) -> Result<EntrySelector, String> {
+    if s.is_empty() {
+        return Err("entry selector must not be empty".to_string());
+    }
+    if let Ok(index) = s.parse::<usize>() {
+        return Ok(EntrySelector::Index(index));
+    }
+    if s.contains(['*', '?', '[', '{']) {
+        return Ok(EntrySelector::Glob(s.to_string()));
+    }
+    Ok(EntrySelector::Name(s.to_string()))
+}
+
+const TICK_MS: u64 = 250;
+
+/// Renders engine events as indicatif progress bars on stderr.
+struct CliProgress {
+    mp: MultiProgress,
+    bar: Mutex<Option<ProgressBar>>,
+    /// Answers to `export-conflict` prompts.
+    conflict_decisions: PendingDecisions<ConflictDecision>,
+    /// Answers to `connection-request` prompts.
+    connection_decisions: PendingDecisions<bool>,
+    /// Cancelled when a share stops by itself.
+    share_expired: CancellationToken,
+}
+
+impl CliProgress {
+    fn new(no_progress: bool) -> Arc<Self> {
+        let draw_target = if no_progress {
+            ProgressDrawTarget::hidden()
+        } else {
+            ProgressDrawTarget::stderr()
+        };
+        Arc::new(Self {
+            mp: MultiProgress::with_draw_target(draw_target),
+            bar: Mutex::new(None),
+            conflict_decisions: PendingDecisions::new(),
+            connection_decisions: PendingDecisions::new(),
+            share_expired: CancellationToken::new(),
+        })
+    }
+
+    /// Asks on the terminal what to do with an existing file. Reading stdin blocks, so the
+    /// prompt runs on its own thread and answers through `conflict_decisions`.
+    fn prompt_conflict(&self, id: u64, path: String, existing_size: u64, incoming_size: u64) {
+        let mp = self.mp.clone();
+        let decisions = self.conflict_decisions.clone();
+        std::thread::spawn(move || {
+            let decision = mp.suspend(|| loop {
+                eprint!(
+                    "{path} exists ({} here, {} incoming). [r]ename, [o]verwrite, [s]kip, [i] skip if identical? Uppercase applies to all: ",
+                    HumanBytes(existing_size),
+                    HumanBytes(incoming_size)
+                );
+                let mut line = String::new();
+                if std::io::stdin().read_line(&mut line).unwrap_or(0) == 0 {
+                    break None;
+                }
+                let answer = line.trim();
+                let policy = match answer.to_ascii_lowercase().as_str() {
+                    "r" => ConflictPolicy::Rename,
+                    "o" => ConflictPolicy::Overwrite,
+                    "s" => ConflictPolicy::Skip,
+                    "i" => ConflictPolicy::SkipIfIdentical,
+                    _ => continue,
+                };
+                break Some(ConflictDecision {
+                    policy,
+                    apply_to_all: answer.chars().all(|c| c.is_ascii_uppercase()),
+                });
+            });
+            match decision {
+                Some(decision) => {
+                    decisions.resolve(id, decision);
+                }
+                // stdin is closed: leave the question unanswered, which keeps the existing file.
+                None => decisions.forget(id),
+            }
+        });
+    }
+
+    /// Asks on the terminal whether a receiver may fetch the share, on its own thread like
+    /// `prompt_conflict`.
+    fn prompt_connection(&self, id: u64, receiver: String) {
+        let mp = self.mp.clone();
+        let decisions = self.connection_decisions.clone();
+        std::thread::spawn(move || {
+            let accepted = mp.suspend(|| {
+                eprint!("{receiver} wants to download the share. Allow? [y/N]: ");
+                let mut line = String::new();
+                match std::io::stdin().read_line(&mut line) {
+                    Ok(0) | Err(_) => None,
+                    Ok(_) => Some(matches!(line.trim(), "y" | "Y" | "yes")),
+                }
+            });
+            match accepted {
+                Some(accepted) => {
+                    decisions.resolve(id, accepted);
+                }
+                // stdin is closed: leave the request unanswered, which denies the receiver.
+                None => decisions.forget(id),
+            }
+        });
+    }
+
+    fn bar(&self, message: &str) -> ProgressBar {
+        let mut bar = self.bar.lock().unwrap();
+        bar.get_or_insert_with(|| {
+            let pb = self.mp.add(ProgressBar::hidden());
+            pb.enable_steady_tick(Duration::from_millis(TICK_MS));
+            pb.set_style(
+                ProgressStyle::with_template("{spinner:.green}{msg} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {bytes}/{total_bytes} {binary_bytes_per_sec}")
+                    .unwrap()
+                    .progress_chars("#>-"),
+            );
+            pb.set_message(message.to_string());
+            pb
+        })
+        .clone()
+    }
+
+    fn finish(&self, line: Option<&str>) {
+        if let Some(pb) = self.bar.lock().unwrap().take() {
+            pb.finish_and_clear();
+        }
+        if let Some(line) = line {
+            self.mp.suspend(|| eprintln!("{line}"));
+        }
+    }
+
+    fn set_progress(&self, message: &str, bytes: u64, total: u64) {
+        // The speed is recomputed by indicatif.
+        let pb = self.bar(message);
+        pb.set_length(total);
+        pb.set_position(bytes);
+    }
+}
+
+impl EventEmitter for CliProgress {
+    fn emit(&self, event: &TransferEvent) -> Result<(), String> {
+        match event {
+            TransferEvent::TransferStarted => {
+                self.bar(" Sending ...");
+            }
+            TransferEvent::ReceiveStarted => {
+                self.bar(" Downloading ...");
+            }
+            TransferEvent::TransferCompleted => self.finish(Some("transfer completed")),
+            TransferEvent::TransferFailed => self.finish(Some("transfer failed")),
+            TransferEvent::ReceiveCompleted | TransferEvent::ExportCompleted => self.finish(None),
+            TransferEvent::ReceiveCancelled => self.finish(Some("receive cancelled")),
+            TransferEvent::TransferProgress {
+                bytes_transferred,
+                total_bytes,
+                ..
+            } => self.set_progress(" Sending ...", *bytes_transferred, *total_bytes),
+            TransferEvent::ReceiveProgress {
+                bytes_transferred,
+                total_bytes,
+                ..
+            } => self.set_progress(" Downloading ...", *bytes_transferred, *total_bytes),
+            TransferEvent::ActiveConnectionCount { count } => {
+                let line = format!("active connections: {count}");
+                self.mp.suspend(|| eprintln!("{line}"));
+            }
+            TransferEvent::ExportStarted { .. } => {
+                // The download bar is done once export begins.
+                self.finish(None);
+                self.bar(" Exporting ...");
+            }
+            TransferEvent::ExportProgress {
+                file_name,
+                bytes_copied,
+                total_bytes,
+                ..
+            } => {
+                let pb = self.bar(" Exporting ...");
+                pb.set_message(format!(" Exporting {file_name}"));
+                pb.set_length(*total_bytes);
+                pb.set_position(*bytes_copied);
+            }
+            TransferEvent::ExportConflict {
+                id,
+                path,
+                existing_size,
+                incoming_size,
+                ..
+            } => self.prompt_conflict(*id, path.clone(), *existing_size, *incoming_size),
+            TransferEvent::ReceiveConflicts { conflicts } => {
+                for conflict in conflicts {
+                    let line = match conflict.action {
+                        ConflictAction::Renamed => format!(
+                            "renamed {} to {} to avoid overwriting an existing file",
+                            conflict.original, conflict.resolved
+                        ),
+                        ConflictAction::Overwritten => format!("overwrote {}", conflict.original),
+                        ConflictAction::Skipped => {
+                            format!("kept existing {}", conflict.original)
+                        }
+                        ConflictAction::Identical => {
+                            format!("{} is already up to date", conflict.original)
+                        }
+                    };
+                    self.mp.suspend(|| eprintln!("{line}"));
+                }
+            }
+            TransferEvent::ShareUpdated {
+                hash,
+                version,
+                size,
+                file_count,
+            } => {
+                let line = format!(
+                    "published version {version}: {file_count} files, {}, hash {hash}",
+                    HumanBytes(*size)
+                );
+                self.mp.suspend(|| eprintln!("{line}"));
+            }
+            TransferEvent::ConnectionRejected {
+                endpoint_id,
+                reason,
+            } => {
+                let line = format!("rejected connection from {endpoint_id}: {reason}");
+                self.mp.suspend(|| eprintln!("{line}"));
+            }
+            TransferEvent::ConnectionRequest {
+                id,
+                endpoint_id,
+                contact_name,
+            } => {
+                let receiver = match contact_name {
+                    Some(name) => format!("{name} ({endpoint_id})"),
+                    None => endpoint_id.clone(),
+                };
+                self.prompt_connection(*id, receiver);
+            }
+            TransferEvent::ReceiverProgress(receiver)
+                if receiver.status != ReceiverStatus::Active =>
+            {
+                let who = match &receiver.contact_name {
+                    Some(name) => format!("{name} ({})", receiver.endpoint_id),
+                    None => receiver.endpoint_id.clone(),
+                };
+                let line = match receiver.status {
+                    ReceiverStatus::Completed => format!("{who} finished downloading"),
+                    _ => format!(
+                        "{who} stopped downloading after {}",
+                        HumanBytes(receiver.bytes_sent)
+                    ),
+                };
+                self.mp.suspend(|| eprintln!("{line}"));
+            }
+            TransferEvent::ShareExpired { reason } => {
+                let line = match reason {
+                    ShareExpiryReason::TimeLimit => "share expired",
+                    ShareExpiryReason::DownloadLimit => "download limit reached",
+                };
+                self.finish(Some(line));
+                self.share_expired.cancel();
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Cancels the returned token on the first Ctrl-C.
+fn cancel_on_ctrl_c() -> CancellationToken {
+    let token = CancellationToken::new();
+    let token2 = token.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            token2.cancel();
+        }
+    });
+    token
+}
+
+/// The address book shared with other tools using the default data directory.
+fn load_contacts() -> Option<AddressBook> {
+    match AddressBook::load(AddressBook::default_path()) {
+        Ok(contacts) => Some(contacts),
+        Err(e) => {
+            tracing::warn!("Failed to load address book: {}", e);
+            None
+        }
+    }
+}
+
+/// Resolves `send --allow` values, given as endpoint IDs or contact names.
+fn allowed_receivers(receivers: &[String]) -> anyhow::Result<Option<HashSet<EndpointId>>> {
+    if receivers.is_empty() {
+        return Ok(None);
+    }
+    let contacts = load_contacts();
+    receivers
+        .iter()
+        .map(|receiver| match EndpointId::from_str(receiver) {
+            Ok(endpoint_id) => Ok(endpoint_id),
+            Err(_) => contacts
+                .as_ref()
+                .and_then(|contacts| contacts.endpoint_id_for(receiver))
+                .with_context(|| format!("{receiver:?} is neither an endpoint ID nor a contact")),
+        })
+        .collect::<anyhow::Result<_>>()
+        .map(Some)
+}
+
+fn build_metadata(paths: &[PathBuf]) -> FileMetadata {
+    let size = paths
+        .iter()
+        .flat_map(|path| {
+            walkdir::WalkDir::new(path)
+                .into_iter()
+                .filter_map(|e| e.ok())
+        })
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|m| m.len())
+        .sum();
+    let file_name = paths[0]
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("item")
+        .to_string();
+    FileMetadata {
+        file_name,
+        item_count: paths.len() as u32,
+        size,
+        thumbnail: None,
+        mime_type: None,
+        items: None,
+        sender_name: None,
+        live: false,
+    }
+}
+
+async fn send(args: SendArgs) -> anyhow::Result<()> {
+    let cancel_token = cancel_on_ctrl_c();
+    let progress = CliProgress::new(args.common.no_progress);
+    let app_handle: AppHandle = Some(progress.clone());
+    let metadata = build_metadata(&args.paths);
+
+    let secret_key = args.common.secret_key()?;
+    let options = SendOptions {
+        relay_mode: args.common.relay,
+        ticket_type: args.ticket_type,
+        magic_ipv4_addr: args.common.magic_ipv4_addr,
+        magic_ipv6_addr: args.common.magic_ipv6_addr,
+        cancel_token: cancel_token.clone(),
+        secret_key,
+        preserve_attributes: args.preserve_attributes,
+        symlink_policy: args.symlinks,
+        use_ignore_files: !args.no_ignore,
+        include: args.include,
+        exclude: args.exclude,
+        strict: args.strict,
+        watch_interval: args.watch.map(Duration::from_secs),
+        expires_after: args.expires_in,
+        max_downloads: args.max_downloads,
+        password: args.common.password,
+        allowed_receivers: allowed_receivers(&args.allow)?,
+        confirm_receivers: args.confirm.map(Duration::from_secs),
+        connection_decisions: progress.connection_decisions.clone(),
+        contacts: load_contacts(),
+        rate_limits: RateLimits {
+            total: args.max_rate,
+            per_receiver: args.max_rate_per_receiver,
+        },
+    };
+    let share = start_share_items(args.paths, options, &app_handle, Some(metadata)).await?;
+
+    println!(
+        "imported {}, {}, hash {}",
+        share.entry_type,
+        HumanBytes(share.size),
+        share.hash
+    );
+    for link in &share.skipped_links {
+        eprintln!("skipped link {} ({:?})", link.name, link.reason);
+    }
+    for entry in &share.skipped {
+        eprintln!("skipped {} ({:?})", entry.name, entry.reason);
+    }
+    println!("to get this data, use");
+    println!("sendme receive {}", share.ticket);
+
+    #[cfg(feature = "clipboard")]
+    if args.clipboard {
+        add_to_clipboard(&share.ticket);
+    }
+
+    tokio::select! {
+        _ = cancel_token.cancelled() => {}
+        _ = progress.share_expired.cancelled() => {}
+    }
+
+    progress.finish(None);
+    println!("shutting down");
+    match tokio::time::timeout(Duration::from_secs(2), share.router.shutdown()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => tracing::warn!("Router shutdown error: {}", e),
+        Err(_) => tracing::warn!("Router shutdown timeout after 2 seconds"),
+    }
+    share.router.endpoint().close().await;
+    let blobs_data_dir = share.blobs_data_dir.clone();
+    drop(share);
+    if let Err(e) = tokio::fs::remove_dir_all(&blobs_data_dir).await {
+        tracing::warn!("Failed to clean up blobs directory: {}", e);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "clipboard")]
+fn add_to_clipboard(ticket: &str) {
+    use std::io::stdout;
+
+    use crossterm::{clipboard::CopyToClipboard, execute};
+
+    execute!(
+        stdout(),
+        CopyToClipboard::to_clipboard_from(format!("sendme receive {ticket}"))
+    )
+    .unwrap_or_else(|e| eprintln!("Failed to copy to clipboard: {e}"));
+}
+
+async fn receive(args: ReceiveArgs) -> anyhow::Result<()> {
+    let progress = CliProgress::new(args.common.no_progress);
+    let app_handle: AppHandle = Some(progress.clone());
+
+    // The blob store exports to absolute paths only.
+    let output_dir = args.output_dir.map(std::path::absolute).transpose()?;
+    let secret_key = args.common.secret_key()?;
+    let control = ReceiveControl::new();
+    control.set_rate_limit(args.max_rate);
+    let options = ReceiveOptions {
+        output_dir,
+        relay_mode: args.common.relay,
+        magic_ipv4_addr: args.common.magic_ipv4_addr,
+        magic_ipv6_addr: args.common.magic_ipv6_addr,
+        cancel_token: cancel_on_ctrl_c(),
+        control,
+        secret_key,
+        contacts: load_contacts(),
+        conflict_policy: args.on_conflict,
+        conflict_decisions: progress.conflict_decisions.clone(),
+        selection: args.only,
+        mirror: args.mirror.then_some(MirrorOptions {
+            delete_extraneous: args.delete,
+        }),
+        latest: args.latest,
+        password: args.common.password,
+    };
+    let result = download(args.ticket, options, app_handle).await;
+    progress.finish(None);
+    let result = result?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&result)?);
+        return Ok(());
+    }
+
+    println!("{}", result.message);
+    println!("saved to {}", result.file_path.display());
+    if result.duration_ms > 0 && !result.was_cached {
+        println!(
+            "{} in {:.1}s, {}/s over {:?} path",
+            HumanBytes(result.bytes_fetched),
+            result.duration_ms as f64 / 1000.0,
+            HumanBytes(result.average_speed_bps as u64),
+            result.connection_path
+        );
+    }
+    Ok(())
+}
+
+async fn metadata(args: MetadataArgs) -> anyhow::Result<()> {
+    let secret_key = args.common.secret_key()?;
+    let options = ReceiveOptions {
+        relay_mode: args.common.relay,
+        magic_ipv4_addr: args.common.magic_ipv4_addr,
+        magic_ipv6_addr: args.common.magic_ipv6_addr,
+        secret_key,
+        contacts: load_contacts(),
+        password: args.common.password,
+        ..Default::default()
+    };
+    let metadata = fetch_metadata(args.ticket, options).await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&metadata)?);
+        return Ok(());
+    }
+
+    if let Some(sender_name) = &metadata.sender_name {
+        println!("from:  {sender_name}");
+    }
+    println!("name:  {}", metadata.file_name);
+    println!("items: {}", metadata.item_count);
+    println!("size:  {}", HumanBytes(metadata.size));
+    if let Some(mime_type) = &metadata.mime_type {
+        println!("type:  {mime_type}");
+    }
+    for item in metadata.items.iter().flatten() {
+        println!("    {} ({})", item.file_name, HumanBytes(item.size));
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        Err(cause) => {
+            if let Some(text) = cause.get(ContextKind::InvalidSubcommand) {
+                eprintln!("{} \"{}\"\n", ErrorKind::InvalidSubcommand, text);
+                eprintln!("Available subcommands are");
+                for cmd in Args::command().get_subcommands() {
+                    eprintln!("    {}", style(cmd.get_name()).bold());
+                }
+                std::process::exit(1);
+            } else {
+                cause.exit();
+            }
+        }
+    };
+    let res = match args.command {
+        Commands::Send(args) => send(args).await,
+        Commands::Receive(args) => receive(args).await,
+        Commands::Metadata(args) => metadata(args).await,
+    };
+    if let Err(e) = &res {
+        eprintln!("{e}");
+    }
+    match res {
+        Ok(()) => std::process::exit(0),
+        Err(_) => std::process::exit(1),
+    }
+}
@@ -1,13 +1,15 @@
 #![allow(dead_code, unused_imports)]
 
-use engine::EventEmitter;
+use engine::{
+    AddrInfoOptions, EventEmitter, ReceiveOptions, RelayModeOption, SendOptions, TransferEvent,
+};
 use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 
 #[derive(Debug, Clone)]
 pub struct MockEvent {
     pub name: String,
-    pub payload: Option<String>,
+    pub event: TransferEvent,
 }
 
 #[derive(Debug, Default)]
@@ -53,18 +55,10 @@ impl MockEventEmitter {
 }
 
 impl EventEmitter for MockEventEmitter {
-    fn emit_event(&self, event_name: &str) -> Result<(), String> {
+    fn emit(&self, event: &TransferEvent) -> Result<(), String> {
         self.events.lock().unwrap().push(MockEvent {
-            name: event_name.to_string(),
-            payload: None,
-        });
-        Ok(())
-    }
-
-    fn emit_event_with_payload(&self, event_name: &str, payload: &str) -> Result<(), String> {
-        self.events.lock().unwrap().push(MockEvent {
-            name: event_name.to_string(),
-            payload: Some(payload.to_string()),
+            name: event.name().to_string(),
+            event: event.clone(),
         });
         Ok(())
     }
@@ -127,3 +121,22 @@ impl TestFixture {
         out
     }
 }
+
+/// Send options that work without reaching public relays: relays disabled and
+/// direct addresses embedded in the ticket.
+pub fn local_send_options() -> SendOptions {
+    SendOptions {
+        relay_mode: RelayModeOption::Disabled,
+        ticket_type: AddrInfoOptions::Addresses,
+        ..Default::default()
+    }
+}
+
+/// Receive options matching `local_send_options`.
+pub fn local_receive_options(output_dir: PathBuf) -> ReceiveOptions {
+    ReceiveOptions {
+        output_dir: Some(output_dir),
+        relay_mode: RelayModeOption::Disabled,
+        ..Default::default()
+    }
+}
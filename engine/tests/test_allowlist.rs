@@ -0,0 +1,191 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, fetch_metadata, list_interrupted_downloads, start_share_items, AppHandle,
+    ReceiveOptions, SendOptions, ShareAccessError, TransferEvent,
+};
+use iroh::SecretKey;
+use std::path::PathBuf;
+
+fn receive_as(key: &SecretKey, output_dir: PathBuf) -> ReceiveOptions {
+    ReceiveOptions {
+        secret_key: Some(key.clone()),
+        ..local_receive_options(output_dir)
+    }
+}
+
+#[tokio::test]
+async fn e2e_only_allowed_receivers_get_the_share() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("report.pdf", b"quarterly numbers");
+    let allowed = SecretKey::generate();
+    let stranger = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        allowed_receivers: Some([allowed.public()].into()),
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let stranger_dir = fixture.output_dir_named("stranger");
+    let error = download(
+        share.ticket.clone(),
+        receive_as(&stranger, stranger_dir.clone()),
+        None,
+    )
+    .await
+    .expect_err("a receiver that is not allowed should be refused");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed),
+        "{error:#}"
+    );
+    assert!(!stranger_dir.join("report.pdf").exists());
+    assert!(
+        !list_interrupted_downloads()
+            .await
+            .unwrap()
+            .iter()
+            .any(|record| record.hash == share.hash),
+        "a refused receive should not be offered for resuming"
+    );
+
+    let rejected = emitter.events_with_name("connection-rejected");
+    assert!(!rejected.is_empty());
+    match &rejected[0].event {
+        TransferEvent::ConnectionRejected {
+            endpoint_id,
+            reason,
+        } => {
+            assert_eq!(*endpoint_id, stranger.public().to_string());
+            assert_eq!(*reason, ShareAccessError::NotAllowed);
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    let recv_dir = fixture.output_dir();
+    download(
+        share.ticket.clone(),
+        receive_as(&allowed, recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("the allowed receiver should get the share");
+    assert_eq!(
+        std::fs::read(recv_dir.join("report.pdf")).unwrap(),
+        b"quarterly numbers"
+    );
+    assert_eq!(
+        emitter.events_with_name("connection-rejected").len(),
+        rejected.len()
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_metadata_is_withheld_from_other_receivers() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("report.pdf", b"quarterly numbers");
+    let stranger = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        allowed_receivers: Some([SecretKey::generate().public()].into()),
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let error = fetch_metadata(
+        share.ticket.clone(),
+        receive_as(&stranger, fixture.output_dir()),
+    )
+    .await
+    .expect_err("a receiver that is not allowed should not see the metadata");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed)
+    );
+    let rejected = emitter.events_with_name("connection-rejected");
+    match &rejected
+        .first()
+        .expect("the denial should be reported")
+        .event
+    {
+        TransferEvent::ConnectionRejected { endpoint_id, .. } => {
+            assert_eq!(*endpoint_id, stranger.public().to_string());
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_allowed_receivers_still_need_the_password() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("report.pdf", b"quarterly numbers");
+    let allowed = SecretKey::generate();
+    let options = SendOptions {
+        allowed_receivers: Some([allowed.public()].into()),
+        password: Some("hunter2".to_string()),
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let error = download(
+        share.ticket.clone(),
+        receive_as(&allowed, fixture.output_dir_named("missing")),
+        None,
+    )
+    .await
+    .expect_err("the password is still required");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::PasswordRequired),
+        "{error:#}"
+    );
+
+    // The password does not help a receiver that is not allowed.
+    let error = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            password: Some("hunter2".to_string()),
+            ..receive_as(&SecretKey::generate(), fixture.output_dir_named("stranger"))
+        },
+        None,
+    )
+    .await
+    .expect_err("a receiver that is not allowed should be refused");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed),
+        "{error:#}"
+    );
+
+    let recv_dir = fixture.output_dir();
+    download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            password: Some("hunter2".to_string()),
+            ..receive_as(&allowed, recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("the allowed receiver with the password should get the share");
+    assert!(recv_dir.join("report.pdf").exists());
+
+    drop(share);
+}
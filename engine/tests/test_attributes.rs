@@ -0,0 +1,168 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{download, start_share_items, SendOptions};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn preserving_send_options() -> SendOptions {
+    SendOptions {
+        preserve_attributes: true,
+        ..local_send_options()
+    }
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn e2e_attributes_are_preserved() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "scripts",
+        &[("run.sh", b"#!/bin/sh\necho hi\n"), ("notes.txt", b"notes")],
+    );
+    std::fs::set_permissions(
+        source.join("run.sh"),
+        std::fs::Permissions::from_mode(0o755),
+    )
+    .unwrap();
+    std::fs::set_permissions(
+        source.join("notes.txt"),
+        std::fs::Permissions::from_mode(0o600),
+    )
+    .unwrap();
+    let modified = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+    std::fs::File::options()
+        .write(true)
+        .open(source.join("notes.txt"))
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    std::fs::create_dir_all(source.join("empty/nested")).unwrap();
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], preserving_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    assert_eq!(share.file_count, 2);
+
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert_eq!(result.total_files, 2);
+    assert_eq!(result.files.len(), 2);
+    assert!(!recv_dir.join(".sendme-attributes.json").exists());
+
+    let mode = |name: &str| {
+        std::fs::metadata(recv_dir.join(name))
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o777
+    };
+    assert_eq!(mode("scripts/run.sh"), 0o755);
+    assert_eq!(mode("scripts/notes.txt"), 0o600);
+    assert_eq!(
+        std::fs::metadata(recv_dir.join("scripts/notes.txt"))
+            .unwrap()
+            .modified()
+            .unwrap(),
+        modified
+    );
+    assert!(recv_dir.join("scripts/empty/nested").is_dir());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_file_named_like_the_manifest_is_received() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file(".sendme-attributes.json", b"{\"files\":{}}");
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert_eq!(result.total_files, 1);
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join(".sendme-attributes.json (2)")).unwrap(),
+        "{\"files\":{}}"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_file_named_like_the_manifest_keeps_its_name_without_a_manifest() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file(".sendme-attributes.json", b"mine");
+    let notes = fixture.create_file("notes.txt", b"notes");
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source, notes], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert_eq!(result.total_files, 2);
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join(".sendme-attributes.json")).unwrap(),
+        "mine"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_modification_time_is_preserved_without_mode() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("report.txt", b"report");
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_123);
+    std::fs::File::options()
+        .write(true)
+        .open(&source)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], preserving_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert_eq!(
+        std::fs::metadata(recv_dir.join("report.txt"))
+            .unwrap()
+            .modified()
+            .unwrap(),
+        modified
+    );
+
+    drop(share);
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{download, start_share, CancellationToken};
+
+#[tokio::test]
+async fn e2e_cancel_running_download() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_large_file("cancel.bin", 48 * 1024 * 1024);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share(source, local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+
+    let cancel_token = CancellationToken::new();
+    let mut options = local_receive_options(recv_dir.clone());
+    options.cancel_token = cancel_token.clone();
+
+    let emitter = MockEventEmitter::new();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        options,
+        Some(emitter.clone()),
+    ));
+
+    tokio::time::timeout(tokio::time::Duration::from_secs(30), async {
+        while !emitter.has_event("receive-started") {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("timed out waiting for receive-started");
+    cancel_token.cancel();
+
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(10), receive)
+        .await
+        .expect("cancelled download should return promptly")
+        .expect("download task should not panic");
+    assert!(result.is_err(), "cancelled download should return an error");
+    assert!(
+        emitter.has_event("receive-cancelled"),
+        "should emit receive-cancelled"
+    );
+    assert!(
+        !emitter.has_event("receive-completed"),
+        "cancelled download must not report completion"
+    );
+    assert!(
+        !recv_dir.join("cancel.bin").exists(),
+        "nothing should be exported after cancellation"
+    );
+
+    engine::discard_interrupted_download(&share.hash)
+        .await
+        .expect("partial data should be removable");
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_cancelled_token_aborts_share_setup() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("never_shared.txt", b"setup is cancelled");
+
+    let options = local_send_options();
+    options.cancel_token.cancel();
+
+    let result = start_share(source, options, None, None).await;
+    assert!(result.is_err(), "cancelled setup should fail");
+}
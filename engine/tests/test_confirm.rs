@@ -0,0 +1,258 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, fetch_metadata, start_share_items, AddressBook, AppHandle, FileMetadata,
+    ReceiveOptions, SendOptions, ShareAccessError, TransferEvent,
+};
+use iroh::SecretKey;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn receive_as(key: &SecretKey, output_dir: PathBuf) -> ReceiveOptions {
+    ReceiveOptions {
+        secret_key: Some(key.clone()),
+        ..local_receive_options(output_dir)
+    }
+}
+
+/// Waits for the `count`th `connection-request` and returns its ID and contact name.
+async fn connection_request(
+    emitter: &Arc<MockEventEmitter>,
+    count: usize,
+) -> (u64, Option<String>) {
+    tokio::time::timeout(Duration::from_secs(30), async {
+        loop {
+            let requests = emitter.events_with_name("connection-request");
+            if let Some(request) = requests.get(count - 1) {
+                match &request.event {
+                    TransferEvent::ConnectionRequest {
+                        id, contact_name, ..
+                    } => break (*id, contact_name.clone()),
+                    other => panic!("unexpected event {other:?}"),
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the share should ask about the receiver")
+}
+
+#[tokio::test]
+async fn e2e_accepted_receivers_get_the_share() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("photo.jpg", b"holiday");
+    let receiver = SecretKey::generate();
+    let mut contacts = AddressBook::load(fixture.output_dir_named("book").join("contacts.json"))
+        .expect("empty address book");
+    contacts
+        .add(&receiver.public().to_string(), "Alice")
+        .expect("add contact");
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        confirm_receivers: Some(Duration::from_secs(30)),
+        contacts: Some(contacts),
+        ..local_send_options()
+    };
+    let decisions = options.connection_decisions.clone();
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        receive_as(&receiver, recv_dir.clone()),
+        None,
+    ));
+    let (id, contact_name) = connection_request(&emitter, 1).await;
+    assert_eq!(contact_name.as_deref(), Some("Alice"));
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!receive.is_finished(), "the share must wait for the answer");
+    assert!(!recv_dir.join("photo.jpg").exists());
+
+    assert!(decisions.resolve(id, true));
+    receive
+        .await
+        .unwrap()
+        .expect("the accepted receiver should get the share");
+    assert_eq!(
+        std::fs::read(recv_dir.join("photo.jpg")).unwrap(),
+        b"holiday"
+    );
+
+    // The answer holds for the rest of the share.
+    download(
+        share.ticket.clone(),
+        receive_as(&receiver, fixture.output_dir_named("again")),
+        None,
+    )
+    .await
+    .expect("the receiver should not be asked about again");
+    assert_eq!(emitter.events_with_name("connection-request").len(), 1);
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_denied_receivers_are_refused() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("photo.jpg", b"holiday");
+    let receiver = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        confirm_receivers: Some(Duration::from_secs(30)),
+        ..local_send_options()
+    };
+    let decisions = options.connection_decisions.clone();
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        receive_as(&receiver, recv_dir.clone()),
+        None,
+    ));
+    let (id, contact_name) = connection_request(&emitter, 1).await;
+    assert_eq!(contact_name, None);
+    assert!(decisions.resolve(id, false));
+
+    let error = receive
+        .await
+        .unwrap()
+        .expect_err("a denied receiver should be refused");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed),
+        "{error:#}"
+    );
+    assert!(!recv_dir.join("photo.jpg").exists());
+
+    let error = fetch_metadata(share.ticket.clone(), receive_as(&receiver, recv_dir))
+        .await
+        .expect_err("a denied receiver should not see the metadata either");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed)
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_unanswered_requests_are_denied_and_asked_again() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("photo.jpg", b"holiday");
+    let receiver = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        confirm_receivers: Some(Duration::from_millis(300)),
+        ..local_send_options()
+    };
+    let decisions = options.connection_decisions.clone();
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let error = download(
+        share.ticket.clone(),
+        receive_as(&receiver, fixture.output_dir_named("first")),
+        None,
+    )
+    .await
+    .expect_err("an unanswered request should deny the receiver");
+    assert_eq!(
+        error.downcast_ref::<ShareAccessError>(),
+        Some(&ShareAccessError::NotAllowed),
+        "{error:#}"
+    );
+    let (first, _) = connection_request(&emitter, 1).await;
+    assert!(!decisions.resolve(first, true), "the request has expired");
+
+    let recv_dir = fixture.output_dir();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        receive_as(&receiver, recv_dir.clone()),
+        None,
+    ));
+    let (second, _) = connection_request(&emitter, 2).await;
+    assert!(decisions.resolve(second, true));
+    receive
+        .await
+        .unwrap()
+        .expect("the receiver should get the share once accepted");
+    assert!(recv_dir.join("photo.jpg").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_metadata_is_a_stub_until_the_receiver_is_accepted() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("photo.jpg", b"holiday");
+    let receiver = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        confirm_receivers: Some(Duration::from_secs(30)),
+        ..local_send_options()
+    };
+    let decisions = options.connection_decisions.clone();
+    let metadata = FileMetadata {
+        file_name: "photo.jpg".into(),
+        item_count: 1,
+        size: 7,
+        thumbnail: Some("data:image/png;base64,dGVzdA==".into()),
+        mime_type: Some("image/jpeg".into()),
+        items: None,
+        sender_name: None,
+        live: false,
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, Some(metadata))
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let stub = fetch_metadata(
+        share.ticket.clone(),
+        receive_as(&receiver, recv_dir.clone()),
+    )
+    .await
+    .expect("an unconfirmed receiver should get a stub");
+    assert_eq!(stub.file_name, "");
+    assert_eq!(stub.size, 7);
+    assert_eq!(stub.thumbnail, None);
+    assert_eq!(stub.mime_type, None);
+    assert!(!emitter.has_event("connection-request"));
+
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        receive_as(&receiver, recv_dir.clone()),
+        None,
+    ));
+    let (id, _) = connection_request(&emitter, 1).await;
+    assert!(decisions.resolve(id, true));
+    receive
+        .await
+        .unwrap()
+        .expect("the accepted receiver should get the share");
+
+    let metadata = fetch_metadata(share.ticket.clone(), receive_as(&receiver, recv_dir))
+        .await
+        .expect("an accepted receiver should get the metadata");
+    assert_eq!(metadata.file_name, "photo.jpg");
+    assert_eq!(metadata.mime_type.as_deref(), Some("image/jpeg"));
+
+    drop(share);
+}
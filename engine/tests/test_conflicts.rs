@@ -1,7 +1,10 @@
 mod common;
 
-use common::{MockEventEmitter, TestFixture};
-use engine::{download, start_share, ReceiveOptions, SendOptions};
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, start_share, start_share_items, ConflictAction, ConflictDecision, ConflictPolicy,
+    ReceiveOptions, SendOptions, TransferEvent,
+};
 
 #[tokio::test]
 async fn e2e_filename_conflict_resolved() {
@@ -87,3 +90,196 @@ async fn e2e_original_file_preserved() {
 
     drop(share);
 }
+
+#[tokio::test]
+async fn e2e_conflict_policies_skip_identical_and_overwrite() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "project",
+        &[("same.txt", b"unchanged"), ("changed.txt", b"new content")],
+    );
+    let recv_dir = fixture.output_dir();
+    std::fs::create_dir_all(recv_dir.join("project")).unwrap();
+    std::fs::write(recv_dir.join("project/same.txt"), b"unchanged").unwrap();
+    std::fs::write(recv_dir.join("project/changed.txt"), b"old").unwrap();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            conflict_policy: ConflictPolicy::SkipIfIdentical,
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    let actions: Vec<(String, ConflictAction)> = result
+        .conflicts
+        .iter()
+        .map(|c| (c.original.clone(), c.action))
+        .collect();
+    assert_eq!(actions.len(), 2);
+    assert!(actions
+        .iter()
+        .any(|(p, a)| p.ends_with("same.txt") && *a == ConflictAction::Identical));
+    assert!(actions
+        .iter()
+        .any(|(p, a)| p.ends_with("changed.txt") && *a == ConflictAction::Renamed));
+    assert!(!recv_dir.join("project/same (1).txt").exists());
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("project/changed (1).txt")).unwrap(),
+        "new content"
+    );
+    assert_eq!(result.files.len(), 1, "the identical file is not exported");
+
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            conflict_policy: ConflictPolicy::Overwrite,
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert!(result
+        .conflicts
+        .iter()
+        .all(|c| c.action == ConflictAction::Overwritten));
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("project/changed.txt")).unwrap(),
+        "new content"
+    );
+    assert!(!recv_dir.join("project/changed (2).txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_conflict_policy_ask_waits_for_decision() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("notes.txt", b"incoming");
+    let recv_dir = fixture.output_dir();
+    std::fs::write(recv_dir.join("notes.txt"), b"mine").unwrap();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let emitter = MockEventEmitter::new();
+    let options = ReceiveOptions {
+        conflict_policy: ConflictPolicy::Ask,
+        ..local_receive_options(recv_dir.clone())
+    };
+    let decisions = options.conflict_decisions.clone();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        options,
+        Some(emitter.clone()),
+    ));
+
+    let id = tokio::time::timeout(tokio::time::Duration::from_secs(30), async {
+        loop {
+            let asked = emitter.events().into_iter().find_map(|e| match e.event {
+                TransferEvent::ExportConflict {
+                    id, incoming_size, ..
+                } => Some((id, incoming_size)),
+                _ => None,
+            });
+            if let Some((id, incoming_size)) = asked {
+                assert_eq!(incoming_size, 8);
+                break id;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("should ask about the existing file");
+    assert!(!receive.is_finished(), "export must wait for the answer");
+
+    assert!(decisions.resolve(
+        id,
+        ConflictDecision {
+            policy: ConflictPolicy::Skip,
+            apply_to_all: false,
+        },
+    ));
+    let result = receive.await.unwrap().expect("download should succeed");
+
+    assert_eq!(result.conflicts[0].action, ConflictAction::Skipped);
+    assert!(result.files.is_empty());
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("notes.txt")).unwrap(),
+        "mine"
+    );
+    assert!(!recv_dir.join("notes (1).txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_cancel_while_asking_forgets_the_question() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("notes.txt", b"incoming");
+    let recv_dir = fixture.output_dir();
+    std::fs::write(recv_dir.join("notes.txt"), b"mine").unwrap();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let emitter = MockEventEmitter::new();
+    let options = ReceiveOptions {
+        conflict_policy: ConflictPolicy::Ask,
+        ..local_receive_options(recv_dir.clone())
+    };
+    let decisions = options.conflict_decisions.clone();
+    let cancel_token = options.cancel_token.clone();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        options,
+        Some(emitter.clone()),
+    ));
+
+    let id = tokio::time::timeout(tokio::time::Duration::from_secs(30), async {
+        loop {
+            let asked = emitter.events().into_iter().find_map(|e| match e.event {
+                TransferEvent::ExportConflict { id, .. } => Some(id),
+                _ => None,
+            });
+            if let Some(id) = asked {
+                break id;
+            }
+            tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("should ask about the existing file");
+
+    cancel_token.cancel();
+    let result = tokio::time::timeout(tokio::time::Duration::from_secs(10), receive)
+        .await
+        .expect("a cancelled receive must not wait for the answer")
+        .unwrap();
+    assert!(result.is_err());
+    assert!(emitter.has_event("receive-cancelled"));
+    assert!(!decisions.resolve(
+        id,
+        ConflictDecision {
+            policy: ConflictPolicy::Overwrite,
+            apply_to_all: false,
+        },
+    ));
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("notes.txt")).unwrap(),
+        "mine"
+    );
+
+    drop(share);
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::TestFixture;
-use engine::{download, start_share, ReceiveOptions, SendOptions};
+use engine::{download, start_share, ConnectionPath, ReceiveOptions, SendOptions};
 
 #[tokio::test]
 async fn e2e_directory_roundtrip() {
@@ -91,3 +91,45 @@ async fn e2e_directory_with_deep_nesting() {
 
     drop(share);
 }
+
+#[tokio::test]
+async fn e2e_directory_receive_result_is_structured() {
+    let fixture = TestFixture::new();
+    let source_dir =
+        fixture.create_dir_with_files("structured", &[("one.txt", b"1"), ("sub/two.txt", b"22")]);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share(source_dir, common::local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+    let info = share.info();
+    assert_eq!(info.file_count, 2);
+    assert_eq!(info.entry_type, "directory");
+    let ticket: iroh_blobs::ticket::BlobTicket = share.ticket.parse().unwrap();
+    assert_eq!(info.endpoint_id, ticket.addr().id.to_string());
+
+    let result = download(
+        share.ticket.clone(),
+        common::local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    assert_eq!(result.total_files, 2);
+    assert_eq!(result.payload_size, 3);
+    assert!(result.bytes_fetched >= result.payload_size);
+    assert!(!result.was_cached);
+    assert_eq!(result.connection_path, ConnectionPath::Direct);
+    assert_eq!(result.files.len(), 2);
+    for file in &result.files {
+        assert!(file.path.starts_with(&recv_dir));
+        assert_eq!(std::fs::metadata(&file.path).unwrap().len(), file.size);
+    }
+
+    let json = serde_json::to_value(&result).expect("result should serialize");
+    assert_eq!(json["totalFiles"], 2);
+    assert_eq!(json["connectionPath"], "direct");
+
+    drop(share);
+}
@@ -1,7 +1,7 @@
 mod common;
 
 use common::{MockEventEmitter, TestFixture};
-use engine::{download, start_share, ReceiveOptions, SendOptions};
+use engine::{download, start_share, ReceiveOptions, SendOptions, TransferEvent};
 
 #[tokio::test]
 async fn e2e_receiver_event_sequence() {
@@ -116,3 +116,69 @@ async fn e2e_invalid_ticket_errors() {
         "download with invalid ticket should return error"
     );
 }
+
+#[tokio::test]
+async fn export_phase_emits_per_file_progress() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "export_dir",
+        &[("small.txt", b"small"), ("other.txt", b"other file")],
+    );
+    let recv_dir = fixture.output_dir();
+    let receiver_emitter = MockEventEmitter::new();
+
+    let share = start_share(source, common::local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+
+    download(
+        share.ticket.clone(),
+        common::local_receive_options(recv_dir),
+        Some(receiver_emitter.clone()),
+    )
+    .await
+    .expect("download should succeed");
+
+    let names = receiver_emitter.event_names();
+    let export_started = names
+        .iter()
+        .position(|n| n == "export-started")
+        .expect("should have export-started event");
+    let export_completed = names
+        .iter()
+        .position(|n| n == "export-completed")
+        .expect("should have export-completed event");
+    let receive_completed = names
+        .iter()
+        .rposition(|n| n == "receive-completed")
+        .expect("should have receive-completed event");
+    assert!(export_started < export_completed);
+    assert!(export_completed < receive_completed);
+
+    let started = receiver_emitter.events_with_name("export-started");
+    assert!(matches!(
+        started[0].event,
+        TransferEvent::ExportStarted { total_files: 2 }
+    ));
+
+    let mut sizes: Vec<u64> = receiver_emitter
+        .events()
+        .iter()
+        .filter_map(|e| match e.event {
+            TransferEvent::ExportProgress {
+                total_files,
+                bytes_copied,
+                total_bytes,
+                ..
+            } if bytes_copied == total_bytes => {
+                assert_eq!(total_files, 2);
+                Some(total_bytes)
+            }
+            _ => None,
+        })
+        .collect();
+    sizes.sort();
+    assert_eq!(sizes, vec![5, 10], "one final progress event per file");
+
+    drop(share);
+}
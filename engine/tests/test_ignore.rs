@@ -0,0 +1,124 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{download, shared_size, start_share_items, SendOptions};
+use std::path::PathBuf;
+
+/// A project directory with ignore files at two levels and a `.git` directory.
+fn create_project(fixture: &TestFixture) -> PathBuf {
+    fixture.create_dir_with_files(
+        "project",
+        &[
+            (".gitignore", b"target/\n*.log\n"),
+            (".sendmeignore", b"secret.txt\n"),
+            ("src/main.rs", b"fn main() {}"),
+            ("src/debug.log", b"log"),
+            ("docs/.gitignore", b"drafts/\n"),
+            ("docs/guide.md", b"guide"),
+            ("docs/drafts/next.md", b"draft"),
+            ("target/out.bin", b"binary"),
+            ("secret.txt", b"secret"),
+            (".git/HEAD", b"ref: refs/heads/main\n"),
+        ],
+    )
+}
+
+fn ignoring_send_options() -> SendOptions {
+    SendOptions {
+        use_ignore_files: true,
+        ..local_send_options()
+    }
+}
+
+async fn shared_names(source: PathBuf, options: SendOptions) -> Vec<String> {
+    let fixture = TestFixture::new();
+    let recv_dir = fixture.output_dir();
+    let share = start_share_items(vec![source], options, &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    drop(share);
+
+    let mut names: Vec<String> = walkdir::WalkDir::new(&recv_dir)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let relative = entry.path().strip_prefix(&recv_dir).unwrap();
+            relative.to_string_lossy().replace('\\', "/")
+        })
+        .collect();
+    names.sort();
+    names
+}
+
+#[tokio::test]
+async fn e2e_ignore_files_are_honoured() {
+    let fixture = TestFixture::new();
+    let source = create_project(&fixture);
+
+    let names = shared_names(source, ignoring_send_options()).await;
+
+    assert_eq!(
+        names,
+        [
+            "project/.gitignore",
+            "project/.sendmeignore",
+            "project/docs/.gitignore",
+            "project/docs/guide.md",
+            "project/src/main.rs",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn e2e_everything_is_shared_without_ignore_files() {
+    let fixture = TestFixture::new();
+    let source = create_project(&fixture);
+
+    let names = shared_names(source, local_send_options()).await;
+
+    assert_eq!(names.len(), 10);
+    assert!(names.contains(&"project/.git/HEAD".to_string()));
+}
+
+#[tokio::test]
+async fn e2e_include_and_exclude_globs() {
+    let fixture = TestFixture::new();
+    let source = create_project(&fixture);
+    let options = SendOptions {
+        include: vec!["*.md".to_string(), "*.log".to_string()],
+        exclude: vec!["guide.md".to_string()],
+        ..ignoring_send_options()
+    };
+
+    let names = shared_names(source, options).await;
+
+    // The include wins over the `*.log` rule of the .gitignore file, but not over the ignored
+    // drafts directory, which is never walked.
+    assert_eq!(names, ["project/src/debug.log"]);
+}
+
+#[tokio::test]
+async fn shared_size_matches_the_share() {
+    let fixture = TestFixture::new();
+    let source = create_project(&fixture);
+    let single = fixture.create_file("single.log", b"a single file is never filtered");
+    let paths = vec![source, single];
+
+    let size = shared_size(&paths, &ignoring_send_options())
+        .await
+        .expect("shared_size should succeed");
+    let share = start_share_items(paths, ignoring_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    assert_eq!(size, share.size);
+    drop(share);
+}
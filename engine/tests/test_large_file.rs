@@ -1,7 +1,7 @@
 mod common;
 
 use common::{MockEventEmitter, TestFixture};
-use engine::{download, start_share, ReceiveOptions, SendOptions};
+use engine::{download, start_share, ReceiveOptions, SendOptions, TransferEvent};
 
 #[tokio::test]
 async fn e2e_large_file_integrity() {
@@ -72,17 +72,14 @@ async fn e2e_progress_events_emitted() {
     );
 
     for event in &progress_events {
-        let payload = event
-            .payload
-            .as_ref()
-            .expect("progress event should have payload");
-        let parts: Vec<&str> = payload.split(':').collect();
-        assert_eq!(
-            parts.len(),
-            3,
-            "progress payload must be '<bytes_transferred>:<total_bytes>:<speed>', got: {}",
-            payload
-        );
+        match event.event {
+            TransferEvent::ReceiveProgress {
+                bytes_transferred,
+                total_bytes,
+                ..
+            } => assert!(bytes_transferred <= total_bytes),
+            ref other => panic!("unexpected receive-progress event: {other:?}"),
+        }
     }
 
     assert!(receiver_emitter.has_event("receive-completed"));
@@ -0,0 +1,106 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, start_share_items, AppHandle, SendOptions, ShareExpiryReason, TransferEvent,
+};
+use std::time::Duration;
+
+/// Waits for the `share-expired` event and returns its reason.
+async fn wait_for_expiry(emitter: &MockEventEmitter) -> ShareExpiryReason {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            if let Some(event) = emitter.events_with_name("share-expired").pop() {
+                match event.event {
+                    TransferEvent::ShareExpired { reason } => return reason,
+                    other => panic!("unexpected event {other:?}"),
+                }
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("the share should expire")
+}
+
+#[tokio::test]
+async fn e2e_share_stops_after_max_downloads() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("docs", &[("a.txt", b"a"), ("b.txt", b"b")]);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        max_downloads: Some(1),
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+    assert!(!share.router.is_shutdown());
+
+    download(
+        share.ticket.clone(),
+        local_receive_options(fixture.output_dir()),
+        None,
+    )
+    .await
+    .expect("the first download should succeed");
+
+    assert_eq!(
+        wait_for_expiry(&emitter).await,
+        ShareExpiryReason::DownloadLimit
+    );
+    assert!(share.router.is_shutdown());
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_share_stops_after_expiry() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("a.txt", b"a");
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        expires_after: Some(Duration::from_millis(300)),
+        max_downloads: Some(5),
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    assert_eq!(
+        wait_for_expiry(&emitter).await,
+        ShareExpiryReason::TimeLimit
+    );
+    assert!(share.router.is_shutdown());
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_share_without_limits_keeps_running() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("a.txt", b"a");
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source], local_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+    for _ in 0..2 {
+        download(
+            share.ticket.clone(),
+            local_receive_options(fixture.output_dir()),
+            None,
+        )
+        .await
+        .expect("download should succeed");
+    }
+
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    assert!(!emitter.has_event("share-expired"));
+    assert!(!share.router.is_shutdown());
+    drop(share);
+}
@@ -0,0 +1,249 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, fetch_metadata, list_interrupted_downloads, resume_download, start_share_items,
+    AppHandle, FileMetadata, FilePreviewItem, ReceiveOptions, SendOptions,
+};
+use std::path::PathBuf;
+use std::time::Duration;
+
+fn live_send_options() -> SendOptions {
+    SendOptions {
+        watch_interval: Some(Duration::from_millis(100)),
+        ..local_send_options()
+    }
+}
+
+fn latest_receive_options(output_dir: PathBuf) -> ReceiveOptions {
+    ReceiveOptions {
+        latest: true,
+        ..local_receive_options(output_dir)
+    }
+}
+
+/// Waits until the share published `count` updates.
+async fn wait_for_updates(emitter: &MockEventEmitter, count: usize) {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        while emitter.events_with_name("share-updated").len() < count {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("the live share should publish an update");
+}
+
+#[tokio::test]
+async fn e2e_live_share_publishes_changes() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("drop", &[("a.txt", b"first")]);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source.clone()], live_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+    assert_eq!(share.file_count, 1);
+
+    std::fs::write(source.join("b.txt"), b"second").unwrap();
+    wait_for_updates(&emitter, 1).await;
+
+    let latest_dir = fixture.output_dir_named("latest");
+    let result = download(
+        share.ticket.clone(),
+        latest_receive_options(latest_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download of the latest version should succeed");
+    assert_eq!(result.total_files, 2);
+    assert_eq!(
+        std::fs::read(latest_dir.join("drop/b.txt")).unwrap(),
+        b"second"
+    );
+
+    // Without `latest` the ticket still gets the version it was created for.
+    let first_dir = fixture.output_dir_named("first");
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(first_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download of the first version should succeed");
+    assert_eq!(result.total_files, 1);
+    assert!(!first_dir.join("drop/b.txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_live_share_is_not_republished_without_changes() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("drop", &[("a.txt", b"a")]);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source.clone()], live_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!emitter.has_event("share-updated"));
+
+    std::fs::remove_file(source.join("a.txt")).unwrap();
+    std::fs::write(source.join("c.txt"), b"c").unwrap();
+    wait_for_updates(&emitter, 1).await;
+
+    let recv_dir = fixture.output_dir();
+    let result = download(
+        share.ticket.clone(),
+        latest_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    assert_eq!(result.total_files, 1);
+    assert!(recv_dir.join("drop/c.txt").exists());
+    assert!(!recv_dir.join("drop/a.txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_latest_works_for_regular_shares() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("single.txt", b"single");
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let result = download(
+        share.ticket.clone(),
+        latest_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    assert_eq!(result.total_files, 1);
+    assert!(recv_dir.join("single.txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_resumed_latest_receive_drops_older_versions() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("drop", &[("a.txt", b"first")]);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source.clone()], live_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    // The fetch completes but the export into a file fails, so the receive is recorded under
+    // the first version.
+    let blocked_output = fixture.create_file("not_a_dir", b"x");
+    assert!(download(
+        share.ticket.clone(),
+        latest_receive_options(blocked_output),
+        None
+    )
+    .await
+    .is_err());
+    let recorded = |hash: String| async move {
+        list_interrupted_downloads()
+            .await
+            .unwrap()
+            .iter()
+            .any(|record| record.hash == hash)
+    };
+    assert!(recorded(share.hash.clone()).await);
+
+    std::fs::write(source.join("b.txt"), b"second").unwrap();
+    wait_for_updates(&emitter, 1).await;
+
+    let recv_dir = fixture.output_dir();
+    let result = resume_download(
+        share.ticket.clone(),
+        latest_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("resume should succeed");
+    assert_eq!(result.total_files, 2);
+    assert!(
+        !recorded(share.hash.clone()).await,
+        "the receive of the first version should be discarded"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_live_share_republishes_metadata() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("drop", &[("a.txt", b"first")]);
+    let notes = fixture.create_file("notes.txt", b"notes");
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let item = |file_name: &str, size: u64, mime_type: &str| FilePreviewItem {
+        file_name: file_name.to_string(),
+        size,
+        thumbnail: None,
+        mime_type: Some(mime_type.to_string()),
+    };
+    let metadata = FileMetadata {
+        file_name: "drop".into(),
+        item_count: 2,
+        size: 10,
+        thumbnail: None,
+        mime_type: Some("application/x-iroh-collection".into()),
+        items: Some(vec![
+            item("drop", 5, "inode/directory"),
+            item("notes.txt", 5, "text/plain"),
+        ]),
+        sender_name: None,
+        live: false,
+    };
+
+    let share = start_share_items(
+        vec![source.clone(), notes],
+        live_send_options(),
+        &app_handle,
+        Some(metadata),
+    )
+    .await
+    .expect("start_share_items should succeed");
+
+    std::fs::write(source.join("b.txt"), b"second").unwrap();
+    wait_for_updates(&emitter, 1).await;
+
+    let fetched = fetch_metadata(
+        share.ticket.clone(),
+        local_receive_options(fixture.output_dir()),
+    )
+    .await
+    .expect("fetch_metadata should succeed");
+    assert!(fetched.live);
+    assert_eq!(fetched.size, 16);
+    assert_eq!(fetched.item_count, 2);
+    let items: Vec<(String, u64, Option<String>)> = fetched
+        .items
+        .unwrap()
+        .into_iter()
+        .map(|item| (item.file_name, item.size, item.mime_type))
+        .collect();
+    assert_eq!(
+        items,
+        [
+            ("drop".into(), 11, Some("inode/directory".into())),
+            ("notes.txt".into(), 5, Some("text/plain".into())),
+        ]
+    );
+
+    drop(share);
+}
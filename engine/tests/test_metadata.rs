@@ -2,7 +2,8 @@ mod common;
 
 use common::TestFixture;
 use engine::{
-    fetch_metadata, start_share, start_share_items, FileMetadata, ReceiveOptions, SendOptions,
+    fetch_metadata, start_share, start_share_items, AddressBook, FileMetadata, ReceiveOptions,
+    SendOptions,
 };
 
 #[tokio::test]
@@ -18,6 +19,8 @@ async fn e2e_metadata_preview() {
         thumbnail: Some("data:image/png;base64,dGVzdA==".into()),
         mime_type: Some("text/plain".into()),
         items: None,
+        sender_name: None,
+        live: false,
     };
 
     let share = start_share(source, SendOptions::default(), None, Some(metadata.clone()))
@@ -55,6 +58,8 @@ async fn e2e_metadata_multi_item() {
         thumbnail: None,
         mime_type: None,
         items: None,
+        sender_name: None,
+        live: false,
     };
 
     let share = start_share_items(
@@ -84,3 +89,60 @@ async fn e2e_metadata_multi_item() {
 
     drop(share);
 }
+
+#[tokio::test]
+async fn metadata_reports_known_sender_from_address_book() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("from_alice.txt", b"hello");
+    let contacts_dir = tempfile::tempdir().unwrap();
+
+    let sender_key = iroh::SecretKey::generate();
+    let mut contacts = AddressBook::load(contacts_dir.path().join("contacts.json")).unwrap();
+    contacts
+        .add(&sender_key.public().to_string(), "Alice's laptop")
+        .unwrap();
+
+    let metadata = FileMetadata {
+        file_name: "from_alice.txt".into(),
+        item_count: 1,
+        size: 5,
+        thumbnail: None,
+        mime_type: None,
+        items: None,
+        // Self-announced names are not trusted by the receiver.
+        sender_name: Some("Mallory".into()),
+        live: false,
+    };
+    let share = start_share(
+        source,
+        SendOptions {
+            secret_key: Some(sender_key),
+            ..common::local_send_options()
+        },
+        None,
+        Some(metadata),
+    )
+    .await
+    .expect("start_share should succeed");
+
+    let known = fetch_metadata(
+        share.ticket.clone(),
+        ReceiveOptions {
+            contacts: Some(contacts),
+            ..common::local_receive_options(fixture.output_dir())
+        },
+    )
+    .await
+    .expect("fetch_metadata should succeed");
+    assert_eq!(known.sender_name.as_deref(), Some("Alice's laptop"));
+
+    let unknown = fetch_metadata(
+        share.ticket.clone(),
+        common::local_receive_options(fixture.output_dir()),
+    )
+    .await
+    .expect("fetch_metadata should succeed");
+    assert_eq!(unknown.sender_name, None);
+
+    drop(share);
+}
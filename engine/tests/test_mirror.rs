@@ -0,0 +1,164 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{download, start_share_items, EntrySelector, MirrorOptions, ReceiveOptions};
+
+#[tokio::test]
+async fn e2e_mirror_only_fetches_changed_files_and_deletes_extraneous() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "share",
+        &[
+            ("notes.txt", b"first version"),
+            ("sub/old.txt", b"removed later"),
+        ],
+    );
+    fixture.create_large_file("share/big.bin", 4 * 1024 * 1024);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source.clone()], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("initial download should succeed");
+    drop(share);
+
+    std::fs::write(source.join("notes.txt"), b"second version").unwrap();
+    std::fs::write(source.join("new.txt"), b"added").unwrap();
+    std::fs::remove_dir_all(source.join("sub")).unwrap();
+    std::fs::write(recv_dir.join("share/local-only.txt"), b"not shared").unwrap();
+    std::fs::write(recv_dir.join("outside.txt"), b"not in a shared dir").unwrap();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            mirror: Some(MirrorOptions {
+                delete_extraneous: true,
+            }),
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("mirror download should succeed");
+
+    let mut diff = result
+        .mirror
+        .clone()
+        .expect("mirror diff should be reported");
+    diff.deleted.sort();
+    assert_eq!(diff.added, vec!["share/new.txt"]);
+    assert_eq!(diff.updated, vec!["share/notes.txt"]);
+    assert_eq!(diff.unchanged, vec!["share/big.bin"]);
+    assert_eq!(
+        diff.deleted,
+        vec!["share/local-only.txt", "share/sub/old.txt"]
+    );
+
+    assert_eq!(result.total_files, 2);
+    assert!(
+        result.bytes_fetched < 1024 * 1024,
+        "the unchanged large file must not be fetched, got {} bytes",
+        result.bytes_fetched
+    );
+    assert!(result.message.starts_with("Mirrored 2 files"));
+
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("share/notes.txt")).unwrap(),
+        "second version"
+    );
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("share/new.txt")).unwrap(),
+        "added"
+    );
+    assert!(!recv_dir.join("share/notes (1).txt").exists());
+    assert!(!recv_dir.join("share/local-only.txt").exists());
+    assert!(!recv_dir.join("share/sub").exists());
+    assert!(recv_dir.join("outside.txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_mirror_of_unchanged_share_fetches_no_entries() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("share", &[("a.txt", b"a"), ("b.txt", b"b")]);
+    let recv_dir = fixture.output_dir();
+    std::fs::create_dir_all(recv_dir.join("share")).unwrap();
+    std::fs::write(recv_dir.join("share/a.txt"), b"a").unwrap();
+    std::fs::write(recv_dir.join("share/b.txt"), b"b").unwrap();
+    std::fs::write(recv_dir.join("share/extra.txt"), b"kept").unwrap();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            mirror: Some(MirrorOptions::default()),
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("mirror download should succeed");
+
+    let diff = result.mirror.expect("mirror diff should be reported");
+    assert_eq!(diff.unchanged.len(), 2);
+    assert!(diff.added.is_empty() && diff.updated.is_empty() && diff.deleted.is_empty());
+    assert_eq!(result.total_files, 0);
+    assert!(result.files.is_empty());
+    assert!(result.conflicts.is_empty());
+    assert!(recv_dir.join("share/extra.txt").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_mirror_of_a_selection_only_deletes_below_the_selected_directories() {
+    let fixture = TestFixture::new();
+    let docs = fixture.create_dir_with_files("docs", &[("a.txt", b"a")]);
+    let photos = fixture.create_dir_with_files("photos", &[("p.jpg", b"p")]);
+    let music = fixture.create_dir_with_files("music", &[("m.mp3", b"m")]);
+    let recv_dir = fixture.output_dir();
+    // `photos` was never received, `music` holds a file the sender does not share.
+    std::fs::create_dir_all(recv_dir.join("docs")).unwrap();
+    std::fs::write(recv_dir.join("docs/stale.txt"), b"removed by the sender").unwrap();
+    std::fs::create_dir_all(recv_dir.join("music")).unwrap();
+    std::fs::write(recv_dir.join("music/local.mp3"), b"not shared").unwrap();
+
+    let share = start_share_items(vec![docs, photos, music], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            selection: vec![EntrySelector::Name("docs".into())],
+            mirror: Some(MirrorOptions {
+                delete_extraneous: true,
+            }),
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("mirror download of a selection should succeed");
+
+    let diff = result.mirror.expect("mirror diff should be reported");
+    assert_eq!(diff.added, vec!["docs/a.txt"]);
+    assert_eq!(diff.deleted, vec!["docs/stale.txt"]);
+    assert!(recv_dir.join("docs/a.txt").exists());
+    assert!(!recv_dir.join("docs/stale.txt").exists());
+    assert!(recv_dir.join("music/local.mp3").exists());
+    assert!(!recv_dir.join("photos").exists());
+
+    drop(share);
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{
+    download, fetch_metadata, start_share_items, FileMetadata, ReceiveOptions, SendOptions,
+    ShareAccessError,
+};
+use std::path::PathBuf;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+fn protected_send_options() -> SendOptions {
+    SendOptions {
+        password: Some(PASSWORD.to_string()),
+        ..local_send_options()
+    }
+}
+
+fn receive_options(output_dir: PathBuf, password: Option<&str>) -> ReceiveOptions {
+    ReceiveOptions {
+        password: password.map(str::to_string),
+        ..local_receive_options(output_dir)
+    }
+}
+
+fn access_error(error: &anyhow::Error) -> Option<ShareAccessError> {
+    error.downcast_ref::<ShareAccessError>().copied()
+}
+
+#[tokio::test]
+async fn e2e_protected_share_needs_the_password() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("secret.txt", b"top secret");
+
+    let share = start_share_items(vec![source], protected_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let missing_dir = fixture.output_dir_named("missing");
+    let error = download(
+        share.ticket.clone(),
+        receive_options(missing_dir.clone(), None),
+        None,
+    )
+    .await
+    .expect_err("a download without the password should fail");
+    assert_eq!(
+        access_error(&error),
+        Some(ShareAccessError::PasswordRequired),
+        "{error:#}"
+    );
+    assert!(!missing_dir.join("secret.txt").exists());
+
+    let wrong_dir = fixture.output_dir_named("wrong");
+    let error = download(
+        share.ticket.clone(),
+        receive_options(wrong_dir.clone(), Some("wrong horse")),
+        None,
+    )
+    .await
+    .expect_err("a download with a wrong password should fail");
+    assert_eq!(
+        access_error(&error),
+        Some(ShareAccessError::WrongPassword),
+        "{error:#}"
+    );
+
+    let recv_dir = fixture.output_dir();
+    download(
+        share.ticket.clone(),
+        receive_options(recv_dir.clone(), Some(PASSWORD)),
+        None,
+    )
+    .await
+    .expect("a download with the password should succeed");
+    assert_eq!(
+        std::fs::read(recv_dir.join("secret.txt")).unwrap(),
+        b"top secret"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_metadata_of_a_protected_share_is_withheld() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("secret.txt", b"top secret");
+    let metadata = FileMetadata {
+        file_name: "secret.txt".to_string(),
+        item_count: 1,
+        size: 10,
+        thumbnail: Some("data:image/png;base64,dGVzdA==".to_string()),
+        mime_type: Some("text/plain".to_string()),
+        items: None,
+        sender_name: None,
+        live: false,
+    };
+
+    let share = start_share_items(
+        vec![source],
+        protected_send_options(),
+        &None,
+        Some(metadata),
+    )
+    .await
+    .expect("start_share_items should succeed");
+
+    let error = fetch_metadata(
+        share.ticket.clone(),
+        receive_options(fixture.output_dir(), None),
+    )
+    .await
+    .expect_err("fetching the metadata without the password should fail");
+    assert_eq!(
+        access_error(&error),
+        Some(ShareAccessError::PasswordRequired)
+    );
+    assert!(!format!("{error:#}").contains("secret.txt"));
+
+    let fetched = fetch_metadata(
+        share.ticket.clone(),
+        receive_options(fixture.output_dir(), Some(PASSWORD)),
+    )
+    .await
+    .expect("fetching the metadata with the password should succeed");
+    assert_eq!(fetched.file_name, "secret.txt");
+    assert!(fetched.thumbnail.is_some());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_password_is_not_needed_for_open_shares() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("open.txt", b"open");
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    // A passphrase the sender does not ask for is accepted.
+    download(
+        share.ticket.clone(),
+        receive_options(recv_dir.clone(), Some("unused")),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    assert!(recv_dir.join("open.txt").exists());
+
+    drop(share);
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{download, start_share_items, ReceiveControl, ReceiveOptions};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn e2e_paused_receive_continues_after_resume() {
+    let fixture = TestFixture::new();
+    let data: Vec<u8> = (0..2 * 1024 * 1024u32).map(|i| (i % 251) as u8).collect();
+    let source = fixture.create_file("archive.zip", &data);
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let emitter = MockEventEmitter::new();
+    let control = ReceiveControl::new();
+    // Paused before any file data arrives, the receive waits as soon as it starts fetching.
+    assert!(control.pause());
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            control: control.clone(),
+            ..local_receive_options(recv_dir.clone())
+        },
+        Some(emitter.clone()),
+    ));
+
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while !emitter.has_event("receive-paused") {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("the receive should report that it is paused");
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert!(!receive.is_finished(), "a paused receive must not finish");
+    assert!(!recv_dir.join("archive.zip").exists());
+
+    assert!(control.resume());
+    let result = tokio::time::timeout(Duration::from_secs(30), receive)
+        .await
+        .expect("the receive should finish once resumed")
+        .unwrap()
+        .expect("download should succeed");
+    assert_eq!(std::fs::read(recv_dir.join("archive.zip")).unwrap(), data);
+    assert!(result.bytes_fetched >= data.len() as u64);
+
+    let names = emitter.event_names();
+    let paused = names.iter().position(|n| n == "receive-paused").unwrap();
+    let resumed = names.iter().position(|n| n == "receive-resumed").unwrap();
+    let completed = names.iter().position(|n| n == "receive-completed").unwrap();
+    assert!(paused < resumed && resumed < completed, "{names:?}");
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_receive_rate_limit() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("archive.zip", &vec![5u8; 256 * 1024]);
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let control = ReceiveControl::new();
+    control.set_rate_limit(Some(512 * 1024));
+    let recv_dir = fixture.output_dir();
+    let started = Instant::now();
+    download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            control,
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    let elapsed = started.elapsed();
+
+    assert!(
+        elapsed >= Duration::from_millis(400),
+        "256 KiB at 512 KiB/s took only {elapsed:?}"
+    );
+    assert!(recv_dir.join("archive.zip").exists());
+
+    drop(share);
+}
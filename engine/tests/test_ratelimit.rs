@@ -0,0 +1,142 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{download, start_share_items, RateLimits, SendOptions};
+use std::time::{Duration, Instant};
+
+#[tokio::test]
+async fn e2e_uploads_stay_within_the_rate_limit() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("video.mp4", &vec![3u8; 256 * 1024]);
+    let options = SendOptions {
+        rate_limits: RateLimits {
+            total: None,
+            per_receiver: Some(512 * 1024),
+        },
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let started = Instant::now();
+    download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    let elapsed = started.elapsed();
+
+    assert!(
+        elapsed >= Duration::from_millis(400),
+        "256 KiB at 512 KiB/s took only {elapsed:?}"
+    );
+    assert_eq!(
+        std::fs::metadata(recv_dir.join("video.mp4")).unwrap().len(),
+        256 * 1024
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_rate_limit_can_be_lifted_while_sharing() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("video.mp4", &vec![3u8; 1024 * 1024]);
+    let options = SendOptions {
+        rate_limits: RateLimits {
+            total: Some(64 * 1024),
+            per_receiver: None,
+        },
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let recv_dir = fixture.output_dir();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    ));
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert!(!receive.is_finished(), "1 MiB at 64 KiB/s takes 16s");
+
+    share.rate_limiter.set_limits(RateLimits::default());
+    assert_eq!(share.rate_limiter.limits(), RateLimits::default());
+    tokio::time::timeout(Duration::from_secs(5), receive)
+        .await
+        .expect("the download should speed up once the limit is lifted")
+        .unwrap()
+        .expect("download should succeed");
+    assert!(recv_dir.join("video.mp4").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_rate_limit_set_while_sending_paces_the_open_connection() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_large_file("video.mp4", 64 * 1024 * 1024);
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+    let bytes_sent = || {
+        share
+            .receivers
+            .snapshot()
+            .iter()
+            .map(|receiver| receiver.bytes_sent)
+            .sum::<u64>()
+    };
+
+    let recv_dir = fixture.output_dir();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    ));
+    // Limit the share as soon as the first payload bytes went out.
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while bytes_sent() == 0 {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("timed out waiting for upload progress");
+    share.rate_limiter.set_limits(RateLimits {
+        total: Some(256 * 1024),
+        per_receiver: None,
+    });
+
+    // Chunks already let through may still be on their way.
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let before = bytes_sent();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    let sent = bytes_sent() - before;
+    assert!(
+        sent <= 512 * 1024,
+        "the connection that is already sending sent {sent} bytes in 1s at 256 KiB/s"
+    );
+    assert!(!receive.is_finished());
+
+    share.rate_limiter.set_limits(RateLimits::default());
+    tokio::time::timeout(Duration::from_secs(60), receive)
+        .await
+        .expect("the download should speed up once the limit is lifted")
+        .unwrap()
+        .expect("download should succeed");
+    assert_eq!(
+        std::fs::metadata(recv_dir.join("video.mp4")).unwrap().len(),
+        64 * 1024 * 1024
+    );
+
+    drop(share);
+}
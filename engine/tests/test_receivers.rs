@@ -0,0 +1,83 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, start_share_items, AppHandle, ReceiveOptions, ReceiverStatus, TransferEvent,
+};
+use iroh::SecretKey;
+use std::path::PathBuf;
+
+fn receive_as(key: &SecretKey, output_dir: PathBuf) -> ReceiveOptions {
+    ReceiveOptions {
+        secret_key: Some(key.clone()),
+        ..local_receive_options(output_dir)
+    }
+}
+
+#[tokio::test]
+async fn e2e_each_receiver_is_tracked() {
+    let fixture = TestFixture::new();
+    let data = vec![7u8; 256 * 1024];
+    let source = fixture.create_file("slides.pdf", &data);
+    let alice = SecretKey::generate();
+    let bob = SecretKey::generate();
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source], local_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+    assert!(share.receivers.snapshot().is_empty());
+
+    // One after the other, as receives of the same share on one machine share a partial store.
+    for (name, key) in [("alice", &alice), ("bob", &bob)] {
+        download(
+            share.ticket.clone(),
+            receive_as(key, fixture.output_dir_named(name)),
+            None,
+        )
+        .await
+        .expect("download should succeed");
+    }
+
+    // The sender sees a request complete shortly after the receiver has the data.
+    let snapshot = tokio::time::timeout(std::time::Duration::from_secs(10), async {
+        loop {
+            let snapshot = share.receivers.snapshot();
+            if snapshot.len() == 2 && snapshot.iter().all(|r| r.status != ReceiverStatus::Active) {
+                break snapshot;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .expect("both downloads should finish on the sender side");
+    for key in [&alice, &bob] {
+        let receiver = snapshot
+            .iter()
+            .find(|r| r.endpoint_id == key.public().to_string())
+            .expect("every receiver should be listed");
+        assert_eq!(receiver.status, ReceiverStatus::Completed);
+        assert_eq!(receiver.bytes_sent, data.len() as u64);
+        assert_eq!(receiver.total_bytes, data.len() as u64);
+    }
+
+    // Every receiver got its own events, ending with its completion.
+    let events: Vec<_> = emitter
+        .events_with_name("receiver-progress")
+        .into_iter()
+        .map(|e| match e.event {
+            TransferEvent::ReceiverProgress(receiver) => receiver,
+            other => panic!("unexpected event {other:?}"),
+        })
+        .collect();
+    for receiver in &snapshot {
+        let last = events
+            .iter()
+            .rfind(|e| e.connection_id == receiver.connection_id)
+            .expect("every receiver should have progress events");
+        assert_eq!(last, receiver);
+    }
+
+    drop(share);
+}
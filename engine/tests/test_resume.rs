@@ -0,0 +1,209 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    download, list_interrupted_downloads, resume_download, start_share, ConnectionPath,
+    EntrySelector, InterruptedDownload, ReceiveOptions, TransferEvent,
+};
+
+async fn find_interrupted(hash: &str) -> Option<InterruptedDownload> {
+    list_interrupted_downloads()
+        .await
+        .expect("listing interrupted downloads should succeed")
+        .into_iter()
+        .find(|d| d.hash == hash)
+}
+
+#[tokio::test]
+async fn e2e_interrupted_download_resumes() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_large_file("resume.bin", 48 * 1024 * 1024);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share(source.clone(), local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+    let hash = share.hash.clone();
+
+    let emitter = MockEventEmitter::new();
+    let receive = tokio::spawn(download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        Some(emitter.clone()),
+    ));
+
+    // Stop the sender as soon as the first payload bytes have arrived.
+    tokio::time::timeout(tokio::time::Duration::from_secs(30), async {
+        while !emitter
+            .events_with_name("receive-progress")
+            .iter()
+            .any(|e| {
+                matches!(
+                    e.event,
+                    TransferEvent::ReceiveProgress {
+                        bytes_transferred: 1..,
+                        ..
+                    }
+                )
+            })
+        {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .expect("timed out waiting for download progress");
+    share.router.shutdown().await.expect("router shutdown");
+    drop(share);
+
+    let result = receive.await.expect("download task should not panic");
+    assert!(
+        result.is_err(),
+        "download should fail when the sender goes away"
+    );
+
+    let record = find_interrupted(&hash)
+        .await
+        .expect("interrupted download should be listed");
+    assert_eq!(record.output_dir.as_deref(), Some(recv_dir.as_path()));
+    assert!(record.bytes_on_disk > 0, "partial data should be kept");
+
+    // Same content shared again gives the same collection hash under a new ticket.
+    let share = start_share(source, local_send_options(), None, None)
+        .await
+        .expect("second start_share should succeed");
+    assert_eq!(share.hash, hash);
+
+    let mut options = local_receive_options(recv_dir.clone());
+    options.output_dir = None;
+    let emitter = MockEventEmitter::new();
+    resume_download(share.ticket.clone(), options, Some(emitter.clone()))
+        .await
+        .expect("resume should succeed");
+
+    // The bar starts at the data kept from the first attempt.
+    let progress = emitter.events_with_name("receive-progress");
+    match &progress[0].event {
+        TransferEvent::ReceiveProgress {
+            bytes_transferred,
+            total_bytes,
+            ..
+        } => {
+            assert!(*bytes_transferred > 0, "the kept data should count");
+            assert!(bytes_transferred < total_bytes);
+        }
+        other => panic!("unexpected receive-progress event: {other:?}"),
+    }
+
+    let received = std::fs::read(recv_dir.join("resume.bin")).expect("file should be exported");
+    assert_eq!(received.len(), 48 * 1024 * 1024);
+    assert!(received
+        .iter()
+        .enumerate()
+        .all(|(i, b)| *b == (i % 251) as u8));
+
+    assert!(
+        find_interrupted(&hash).await.is_none(),
+        "completed download should no longer be listed"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_receive_failing_before_the_payload_is_not_recorded() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("only.txt", b"data");
+
+    let share = start_share(source, local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+
+    download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            selection: vec![EntrySelector::Name("missing.txt".into())],
+            ..local_receive_options(fixture.output_dir())
+        },
+        None,
+    )
+    .await
+    .expect_err("a selection matching nothing should fail");
+    assert!(
+        find_interrupted(&share.hash).await.is_none(),
+        "nothing was fetched that could be resumed"
+    );
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_resume_without_partial_data_errors() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("fresh.txt", b"nothing to resume");
+
+    let share = start_share(source, local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+
+    download(
+        share.ticket.clone(),
+        local_receive_options(fixture.output_dir()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+
+    let result = resume_download(
+        share.ticket.clone(),
+        local_receive_options(fixture.output_dir_named("again")),
+        None,
+    )
+    .await;
+    assert!(result.is_err(), "resume needs an interrupted download");
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_cached_download_reports_real_sizes() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "cached_dir",
+        &[("a.txt", b"aaaa"), ("nested/b.txt", b"bbbbbbbb")],
+    );
+
+    let share = start_share(source, local_send_options(), None, None)
+        .await
+        .expect("start_share should succeed");
+    let ticket = share.ticket.clone();
+    let hash = share.hash.clone();
+
+    // The output "directory" is a file, so the fetch completes but the export fails.
+    let blocked_output = fixture.create_file("not_a_dir", b"x");
+    download(ticket.clone(), local_receive_options(blocked_output), None)
+        .await
+        .expect_err("export into a file should fail");
+
+    // With the sender gone, the second attempt can only be served from the local store.
+    share.router.shutdown().await.expect("router shutdown");
+    drop(share);
+
+    let recv_dir = fixture.output_dir();
+    let result = resume_download(ticket, local_receive_options(recv_dir.clone()), None)
+        .await
+        .expect("cached receive should succeed");
+
+    assert!(result.was_cached);
+    assert_eq!(result.bytes_fetched, 0);
+    assert_eq!(result.connection_path, ConnectionPath::Local);
+    assert_eq!(result.files.len(), 2);
+    assert_eq!(result.total_files, 2);
+    assert_eq!(result.payload_size, 12);
+    assert!(result.conflicts.is_empty());
+    assert_eq!(result.message, "Downloaded 2 files, 12 bytes");
+    assert_eq!(
+        std::fs::read(recv_dir.join("cached_dir/nested/b.txt")).unwrap(),
+        b"bbbbbbbb"
+    );
+    assert!(find_interrupted(&hash).await.is_none());
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{download, start_share_items, EntrySelector, ReceiveOptions};
+
+#[tokio::test]
+async fn e2e_selective_download_fetches_only_selected_entries() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files(
+        "share",
+        &[
+            ("logs/app.log", b"log line"),
+            ("logs/old.txt", b"old"),
+            ("readme.md", b"readme"),
+        ],
+    );
+    fixture.create_large_file("share/big.bin", 4 * 1024 * 1024);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            selection: vec![EntrySelector::Glob("share/logs/*.log".into())],
+            ..local_receive_options(recv_dir.clone())
+        },
+        None,
+    )
+    .await
+    .expect("selective download should succeed");
+
+    assert_eq!(result.total_files, 1);
+    assert_eq!(result.payload_size, 8);
+    assert!(
+        result.bytes_fetched < 1024 * 1024,
+        "the large entry must not be fetched, got {} bytes",
+        result.bytes_fetched
+    );
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("share/logs/app.log")).unwrap(),
+        "log line"
+    );
+    assert!(!recv_dir.join("share/logs/old.txt").exists());
+    assert!(!recv_dir.join("share/big.bin").exists());
+    assert!(!recv_dir.join("share/readme.md").exists());
+
+    // The rest can still be fetched afterwards; the selected entry is already local.
+    let all_dir = fixture.output_dir_named("all");
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(all_dir.clone()),
+        None,
+    )
+    .await
+    .expect("full download should succeed");
+    assert_eq!(result.total_files, 4);
+    assert!(all_dir.join("share/big.bin").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_selection_matching_nothing_fails() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_file("only.txt", b"data");
+
+    let share = start_share_items(vec![source], local_send_options(), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    let result = download(
+        share.ticket.clone(),
+        ReceiveOptions {
+            selection: vec![EntrySelector::Name("missing.txt".into())],
+            ..local_receive_options(fixture.output_dir())
+        },
+        None,
+    )
+    .await;
+    let err = result.expect_err("download should fail").to_string();
+    assert!(
+        err.contains("matches no entries"),
+        "unexpected error: {err}"
+    );
+
+    drop(share);
+}
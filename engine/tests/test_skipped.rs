@@ -0,0 +1,96 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{local_send_options, MockEventEmitter, TestFixture};
+use engine::{
+    start_share_items, AppHandle, EntrySkipReason, SendOptions, SkippedEntry, TransferEvent,
+};
+use std::path::PathBuf;
+
+/// A shared directory with a file whose name cannot be shared, a socket and an empty
+/// directory shared next to it.
+fn create_tree(fixture: &TestFixture) -> (PathBuf, PathBuf, std::os::unix::net::UnixListener) {
+    let source =
+        fixture.create_dir_with_files("share", &[("good.txt", b"good"), ("bad\\name.txt", b"bad")]);
+    let socket = std::os::unix::net::UnixListener::bind(source.join("socket")).unwrap();
+    let empty = fixture.dir.path().join("empty");
+    std::fs::create_dir_all(&empty).unwrap();
+    (source, empty, socket)
+}
+
+fn sorted(mut skipped: Vec<SkippedEntry>) -> Vec<(String, EntrySkipReason)> {
+    skipped.sort_by(|a, b| a.name.cmp(&b.name));
+    skipped.into_iter().map(|e| (e.name, e.reason)).collect()
+}
+
+#[tokio::test]
+async fn skipped_entries_are_reported() {
+    let fixture = TestFixture::new();
+    let (source, empty, _socket) = create_tree(&fixture);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+
+    let share = start_share_items(vec![source, empty], local_send_options(), &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    assert_eq!(share.file_count, 1);
+    let expected = vec![
+        ("empty".to_string(), EntrySkipReason::NoFiles),
+        (
+            "share/bad\\name.txt".to_string(),
+            EntrySkipReason::InvalidName,
+        ),
+        ("share/socket".to_string(), EntrySkipReason::Unsupported),
+    ];
+    assert_eq!(sorted(share.info().skipped), expected);
+
+    let warnings = emitter.events_with_name("share-warnings");
+    assert_eq!(warnings.len(), 1);
+    match &warnings[0].event {
+        TransferEvent::ShareWarnings { skipped } => {
+            assert_eq!(sorted(skipped.clone()), expected)
+        }
+        other => panic!("unexpected event {other:?}"),
+    }
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn strict_mode_fails_the_share() {
+    let fixture = TestFixture::new();
+    let (source, _empty, _socket) = create_tree(&fixture);
+    let options = SendOptions {
+        strict: true,
+        ..local_send_options()
+    };
+
+    let error = start_share_items(vec![source], options, &None, None)
+        .await
+        .err()
+        .expect("a strict share with skipped entries should fail");
+
+    assert!(error.to_string().contains("share/socket"), "{error}");
+}
+
+#[tokio::test]
+async fn nothing_is_reported_for_a_clean_share() {
+    let fixture = TestFixture::new();
+    let source = fixture.create_dir_with_files("share", &[("a.txt", b"a")]);
+    let emitter = MockEventEmitter::new();
+    let app_handle: AppHandle = Some(emitter.clone());
+    let options = SendOptions {
+        strict: true,
+        ..local_send_options()
+    };
+
+    let share = start_share_items(vec![source], options, &app_handle, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    assert!(share.skipped.is_empty());
+    assert!(!emitter.has_event("share-warnings"));
+    drop(share);
+}
@@ -0,0 +1,154 @@
+#![cfg(unix)]
+
+mod common;
+
+use common::{local_receive_options, local_send_options, TestFixture};
+use engine::{
+    download, start_share_items, LinkSkipReason, SendOptions, SkippedLink, SymlinkPolicy,
+};
+use std::os::unix::fs::symlink;
+use std::path::PathBuf;
+
+/// A shared directory with a file link, a directory link, a link leaving the share,
+/// a broken link and a link back to the shared directory.
+fn create_tree(fixture: &TestFixture) -> PathBuf {
+    fixture.create_file("outside.txt", b"outside");
+    let source = fixture.create_dir_with_files("share", &[("a.txt", b"a"), ("sub/b.txt", b"b")]);
+    symlink("a.txt", source.join("link_file")).unwrap();
+    symlink("sub", source.join("link_dir")).unwrap();
+    symlink("../outside.txt", source.join("escape")).unwrap();
+    symlink("missing", source.join("broken")).unwrap();
+    symlink(".", source.join("loop")).unwrap();
+    source
+}
+
+fn send_options(symlink_policy: SymlinkPolicy) -> SendOptions {
+    SendOptions {
+        symlink_policy,
+        ..local_send_options()
+    }
+}
+
+fn sorted(mut links: Vec<SkippedLink>) -> Vec<(String, LinkSkipReason)> {
+    links.sort_by(|a, b| a.name.cmp(&b.name));
+    links.into_iter().map(|l| (l.name, l.reason)).collect()
+}
+
+#[tokio::test]
+async fn skipped_links_are_reported() {
+    let fixture = TestFixture::new();
+    let source = create_tree(&fixture);
+
+    let share = start_share_items(vec![source], send_options(SymlinkPolicy::Skip), &None, None)
+        .await
+        .expect("start_share_items should succeed");
+
+    assert_eq!(share.file_count, 2);
+    let skipped = sorted(share.info().skipped_links);
+    assert_eq!(skipped.len(), 5);
+    assert!(skipped
+        .iter()
+        .all(|(_, reason)| *reason == LinkSkipReason::Policy));
+    assert_eq!(skipped[0].0, "share/broken");
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_followed_links_stay_inside_the_share() {
+    let fixture = TestFixture::new();
+    let source = create_tree(&fixture);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(
+        vec![source],
+        send_options(SymlinkPolicy::Follow),
+        &None,
+        None,
+    )
+    .await
+    .expect("start_share_items should succeed");
+
+    assert_eq!(
+        sorted(share.skipped_links.clone()),
+        vec![
+            ("share/broken".to_string(), LinkSkipReason::Broken),
+            ("share/escape".to_string(), LinkSkipReason::OutsideShare),
+            ("share/loop".to_string(), LinkSkipReason::Loop),
+        ]
+    );
+
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    assert_eq!(result.total_files, 4);
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("share/link_file")).unwrap(),
+        "a"
+    );
+    assert_eq!(
+        std::fs::read_to_string(recv_dir.join("share/link_dir/b.txt")).unwrap(),
+        "b"
+    );
+    assert!(!recv_dir.join("share/link_file").is_symlink());
+    assert!(!recv_dir.join("share/escape").exists());
+
+    drop(share);
+}
+
+#[tokio::test]
+async fn e2e_preserved_links_are_recreated() {
+    let fixture = TestFixture::new();
+    let source = create_tree(&fixture);
+    let recv_dir = fixture.output_dir();
+
+    let share = start_share_items(
+        vec![source],
+        send_options(SymlinkPolicy::Preserve),
+        &None,
+        None,
+    )
+    .await
+    .expect("start_share_items should succeed");
+
+    assert_eq!(share.file_count, 2);
+    assert_eq!(
+        sorted(share.skipped_links.clone()),
+        vec![
+            ("share/escape".to_string(), LinkSkipReason::OutsideShare),
+            ("share/loop".to_string(), LinkSkipReason::OutsideShare),
+        ]
+    );
+
+    let result = download(
+        share.ticket.clone(),
+        local_receive_options(recv_dir.clone()),
+        None,
+    )
+    .await
+    .expect("download should succeed");
+    assert_eq!(result.total_files, 2);
+
+    let share_dir = recv_dir.join("share");
+    assert_eq!(
+        std::fs::read_link(share_dir.join("link_file")).unwrap(),
+        PathBuf::from("a.txt")
+    );
+    assert_eq!(
+        std::fs::read_link(share_dir.join("link_dir")).unwrap(),
+        PathBuf::from("sub")
+    );
+    assert_eq!(
+        std::fs::read_to_string(share_dir.join("link_dir/b.txt")).unwrap(),
+        "b"
+    );
+    assert!(share_dir.join("broken").is_symlink());
+    assert!(!share_dir.join("escape").exists());
+    assert!(!share_dir.join("loop").exists());
+
+    drop(share);
+}
@@ -1,9 +1,12 @@
 use crate::features::thumbnail::generate_thumbnail;
-use crate::state::{AppStateMutex, ShareHandle};
+use crate::state::{ActiveReceive, AppState, AppStateMutex, ShareHandle};
 use engine::{
     core::types::{get_or_create_secret, FileMetadata, FilePreviewItem},
-    download, fetch_metadata, AddrInfoOptions, AppHandle, EventEmitter, ReceiveOptions,
-    RelayModeOption, SendOptions,
+    discard_interrupted_download, download, fetch_metadata, list_interrupted_downloads,
+    resume_download, AddrInfoOptions, AddressBook, AppHandle, CancellationToken, ConflictDecision,
+    ConflictPolicy, Contact, EntrySelector, EventEmitter, IdentityLease, InterruptedDownload,
+    MirrorOptions, RateLimits, ReceiveControl, ReceiveOptions, ReceiveResult, ReceiverProgress,
+    RelayModeOption, SendOptions, ShareInfo, SymlinkPolicy, TransferEvent,
 };
 use iroh::{endpoint::presets, Endpoint};
 use n0_watcher::Watcher;
@@ -11,6 +14,7 @@ use std::collections::BTreeMap;
 use std::path::Path;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tauri::{Emitter, Manager, State};
@@ -22,6 +26,71 @@ pub struct RelayConfigArg {
     pub auth_token: Option<String>,
 }
 
+/// Optional limits after which a share stops on its own.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareLimitsArg {
+    pub expires_in_secs: Option<u64>,
+    pub max_downloads: Option<u64>,
+}
+
+/// Optional settings of a new share, see `send_items`. Missing fields take their defaults.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ShareOptionsArg {
+    /// Send permissions and timestamps along. Older receivers save them as an extra
+    /// `.sendme-attributes.json` file, so this is off unless asked for.
+    pub preserve_attributes: bool,
+    pub symlink_policy: SymlinkPolicy,
+    /// Leave out what `.gitignore` and `.sendmeignore` files list, and `.git` directories.
+    pub use_ignore_files: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    /// Rescan the shared paths and publish changes under the same ticket.
+    pub watch: bool,
+    pub limits: ShareLimitsArg,
+    pub password: Option<String>,
+    /// Endpoint IDs of the only receivers that may fetch the share.
+    pub allowed_receivers: Option<Vec<String>>,
+    pub confirm_timeout_secs: Option<u64>,
+    pub rate_limits: RateLimits,
+}
+
+impl ShareOptionsArg {
+    /// Send options of an app share. `get_file_size` takes the same options, so announced
+    /// sizes match what is shared.
+    fn send_options(self) -> Result<SendOptions, String> {
+        let allowed_receivers = self
+            .allowed_receivers
+            .map(|receivers| {
+                receivers
+                    .iter()
+                    .map(|receiver| {
+                        iroh::EndpointId::from_str(receiver.trim())
+                            .map_err(|e| format!("Invalid receiver endpoint ID {receiver:?}: {e}"))
+                    })
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?;
+        Ok(SendOptions {
+            ticket_type: AddrInfoOptions::RelayAndAddresses,
+            preserve_attributes: self.preserve_attributes,
+            symlink_policy: self.symlink_policy,
+            use_ignore_files: self.use_ignore_files,
+            include: self.include,
+            exclude: self.exclude,
+            watch_interval: self.watch.then_some(LIVE_SHARE_INTERVAL),
+            expires_after: self.limits.expires_in_secs.map(Duration::from_secs),
+            max_downloads: self.limits.max_downloads,
+            password: self.password.filter(|password| !password.is_empty()),
+            allowed_receivers,
+            confirm_receivers: self.confirm_timeout_secs.map(Duration::from_secs),
+            rate_limits: self.rate_limits,
+            ..Default::default()
+        })
+    }
+}
+
 pub fn build_relay_mode(arg: Option<RelayConfigArg>) -> Result<RelayModeOption, String> {
     match arg {
         None => Ok(RelayModeOption::Default),
@@ -204,37 +273,114 @@ pub async fn get_relay_status(
     })
 }
 
-// Wrapper for Tauri AppHandle that implements EventEmitter
-struct TauriEventEmitter {
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiveEventPayload<'a> {
+    pub ticket: &'a str,
+    #[serde(flatten)]
+    pub event: &'a TransferEvent,
+}
+
+// Emitter for a single receive: tags every event with the ticket the receive is tracked by in
+// `active_receives`, so the frontend can tell concurrent receives apart.
+struct ReceiveEventEmitter {
     app_handle: tauri::AppHandle,
+    ticket: String,
 }
 
-impl EventEmitter for TauriEventEmitter {
-    fn emit_event(&self, event_name: &str) -> Result<(), String> {
+impl EventEmitter for ReceiveEventEmitter {
+    fn emit(&self, event: &TransferEvent) -> Result<(), String> {
         self.app_handle
-            .emit(event_name, ())
+            .emit(
+                event.name(),
+                ReceiveEventPayload {
+                    ticket: &self.ticket,
+                    event,
+                },
+            )
             .map_err(|e| e.to_string())
     }
+}
+
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareEventPayload<'a> {
+    pub share_id: &'a str,
+    #[serde(flatten)]
+    pub event: &'a TransferEvent,
+}
+
+// Emitter for a single share: tags every event with the share ID so the frontend can tell
+// concurrent shares apart, and keeps the share's transport flag up to date.
+struct ShareEventEmitter {
+    app_handle: tauri::AppHandle,
+    share_id: String,
+    is_transporting: Arc<AtomicBool>,
+}
+
+impl ShareEventEmitter {
+    fn track_transport(&self, event: &TransferEvent) {
+        match event {
+            TransferEvent::TransferStarted => self.is_transporting.store(true, Ordering::SeqCst),
+            TransferEvent::TransferCompleted
+            | TransferEvent::TransferFailed
+            | TransferEvent::ShareExpired { .. } => {
+                self.is_transporting.store(false, Ordering::SeqCst)
+            }
+            _ => {}
+        }
+    }
+
+    /// The engine stopped an expired share; drop it from the app state as `stop_sharing` would,
+    /// so it is no longer listed and its identity lease is released.
+    fn forget_expired(&self) {
+        let app_handle = self.app_handle.clone();
+        let share_id = self.share_id.clone();
+        tauri::async_runtime::spawn(async move {
+            let state = app_handle.state::<AppStateMutex>();
+            if let Err(e) = remove_share(&state, &share_id).await {
+                tracing::warn!(share_id = %share_id, "failed to stop expired share: {}", e);
+            }
+        });
+    }
+}
 
-    fn emit_event_with_payload(&self, event_name: &str, payload: &str) -> Result<(), String> {
+impl EventEmitter for ShareEventEmitter {
+    fn emit(&self, event: &TransferEvent) -> Result<(), String> {
+        self.track_transport(event);
+        if let TransferEvent::ShareExpired { .. } = event {
+            self.forget_expired();
+        }
         self.app_handle
-            .emit(event_name, payload)
+            .emit(
+                event.name(),
+                ShareEventPayload {
+                    share_id: &self.share_id,
+                    event,
+                },
+            )
             .map_err(|e| e.to_string())
     }
 }
 
-/// Get file or directory size
+#[derive(Debug, Clone, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareStarted {
+    pub share_id: String,
+    #[serde(flatten)]
+    pub info: ShareInfo,
+}
+
+/// Get file or directory size, counting only what a share with `options` would include.
 #[tauri::command]
-pub async fn get_file_size(path: String) -> Result<u64, String> {
+pub async fn get_file_size(path: String, options: Option<ShareOptionsArg>) -> Result<u64, String> {
     let path = PathBuf::from(path);
 
     if !path.exists() {
         return Err("Path does not exist".to_string());
     }
 
-    tokio::task::spawn_blocking(move || get_total_size(&path))
-        .await
-        .map_err(|e| format!("Task join error: {}", e))?
+    get_total_size(&path, &options.unwrap_or_default().send_options()?).await
 }
 
 #[tauri::command]
@@ -267,18 +413,29 @@ pub async fn start_sharing(
     relay: Option<RelayConfigArg>,
     state: State<'_, AppStateMutex>,
     app_handle: tauri::AppHandle,
-) -> Result<String, String> {
-    send_items(vec![path], relay, state, app_handle).await
+) -> Result<ShareStarted, String> {
+    send_items(vec![path], relay, None, state, app_handle).await
 }
 
-/// New interface to start_sharing multiple items at once
+/// New interface to start_sharing multiple items at once.
+/// Several shares can be live at the same time; each gets its own share ID.
+/// All settings are in `options`, see `ShareOptionsArg`:
+/// With `preserve_attributes` file permissions and timestamps are sent along, with
+/// `use_ignore_files` shared folders honour their ignore files.
+/// With `watch` the shared paths are rescanned and changes published under the same ticket.
+/// With `password` receivers must know it to see or fetch anything, with `allowed_receivers`
+/// only these endpoint IDs may; others are reported as `connection-rejected`.
+/// With `confirm_timeout_secs` every new receiver is announced as `connection-request` and
+/// waits up to that long for `respond_connection_request`.
+/// `rate_limits` caps the upload rate and can be changed later with `set_share_rate_limits`.
 #[tauri::command]
 pub async fn send_items(
     paths: Vec<String>,
     relay: Option<RelayConfigArg>,
+    options: Option<ShareOptionsArg>,
     state: State<'_, AppStateMutex>,
     app_handle: tauri::AppHandle,
-) -> Result<String, String> {
+) -> Result<ShareStarted, String> {
     // Validate input before doing any work.
     if paths.is_empty() {
         return Err("No paths provided".to_string());
@@ -286,97 +443,108 @@ pub async fn send_items(
 
     let path_bufs: Vec<PathBuf> = paths.into_iter().map(PathBuf::from).collect();
 
-    // Reserve slot before expensive setup to avoid concurrent start_sharing races.
-    {
-        let mut app_state = state.lock().await;
-        if app_state.current_share.is_some() || app_state.is_share_starting {
-            return Err("Already sharing a file. Please stop current share first.".to_string());
-        }
-        app_state.is_share_starting = true;
-    }
-
-    let start_result = async {
-        // Prepare metadata outside the state mutex.
-        let metadata = build_send_metadata(&path_bufs).await?;
-        tracing::info!(
-            first_path_stem = ?path_bufs[0].file_stem(),
-            total_size = metadata.size,
-            has_thumbnail = metadata.thumbnail.is_some(),
-            "share metadata prepared for multiple items"
-        );
-
-        // Create send options from relay settings (custom falls back to public if unreachable).
-        let (relay_mode, fell_back_to_public) = resolve_relay_mode_with_fallback(relay).await?;
-        if fell_back_to_public {
-            // Surface the silent custom->public fallback so the user knows this
-            // transfer is riding public relays despite their custom config.
-            let _ = app_handle.emit("relay-fell-back", "send");
-        }
-        let options = SendOptions {
-            relay_mode,
-            ticket_type: AddrInfoOptions::RelayAndAddresses,
-            magic_ipv4_addr: None,
-            magic_ipv6_addr: None,
-        };
-
-        // Wrap the app_handle in our EventEmitter implementation.
-        let emitter = Arc::new(TauriEventEmitter {
-            app_handle: app_handle.clone(),
-        });
-        let boxed_handle: AppHandle = Some(emitter);
-
-        // Start sharing multiple files/folders via core send pipeline.
-        let result = engine::core::send::start_share_items(
-            path_bufs.clone(),
-            options,
-            &boxed_handle,
-            Some(metadata),
-        )
-        .await
-        .map_err(|e| format!("Failed to start sharing: {}", e))?;
-        Ok((result.ticket.clone(), path_bufs, result))
+    // Allocate the ID up front so events emitted during setup are already tagged.
+    let share_id = state.lock().await.allocate_share_id();
+    let is_transporting = Arc::new(AtomicBool::new(false));
+
+    // Prepare metadata outside the state mutex, with the same filters as the share itself.
+    let mut options = options.unwrap_or_default().send_options()?;
+    let metadata = build_send_metadata(&path_bufs, &options).await?;
+    tracing::info!(
+        share_id = %share_id,
+        first_path_stem = ?path_bufs[0].file_stem(),
+        total_size = metadata.size,
+        has_thumbnail = metadata.thumbnail.is_some(),
+        "share metadata prepared for multiple items"
+    );
+
+    // Create send options from relay settings (custom falls back to public if unreachable).
+    let (relay_mode, fell_back_to_public) = resolve_relay_mode_with_fallback(relay).await?;
+    if fell_back_to_public {
+        // Surface the silent custom->public fallback so the user knows this
+        // transfer is riding public relays despite their custom config.
+        let _ = app_handle.emit("relay-fell-back", "send");
     }
-    .await;
+    let identity_lease = {
+        let app_state = state.lock().await;
+        options.connection_decisions = app_state.connection_decisions.clone();
+        options.contacts = app_state.contacts.clone();
+        lease_identity(&app_state, &app_handle, "send")
+    };
+    options.relay_mode = relay_mode;
+    options.secret_key = identity_lease.as_ref().map(|l| l.secret_key().clone());
 
-    match start_result {
-        Ok((ticket, paths, result)) => {
-            let mut app_state = state.lock().await;
-            app_state.is_share_starting = false;
+    // Wrap the app_handle in our EventEmitter implementation.
+    let emitter = Arc::new(ShareEventEmitter {
+        app_handle: app_handle.clone(),
+        share_id: share_id.clone(),
+        is_transporting: is_transporting.clone(),
+    });
+    let boxed_handle: AppHandle = Some(emitter);
 
-            if app_state.current_share.is_some() {
-                return Err("Already sharing a file. Please stop current share first.".to_string());
-            }
+    // Start sharing multiple files/folders via core send pipeline.
+    let result = engine::core::send::start_share_items(
+        path_bufs.clone(),
+        options,
+        &boxed_handle,
+        Some(metadata),
+    )
+    .await
+    .map_err(|e| format!("Failed to start sharing: {}", e))?;
+
+    // Keep full send result alive to preserve router/temp_tag lifecycle.
+    let info = result.info();
+    let primary = path_bufs
+        .first()
+        .cloned()
+        .unwrap_or_else(|| PathBuf::from("."));
+    state.lock().await.shares.insert(
+        share_id.clone(),
+        ShareHandle::new(
+            info.ticket.clone(),
+            primary,
+            result,
+            is_transporting,
+            identity_lease,
+        ),
+    );
+
+    Ok(ShareStarted { share_id, info })
+}
 
-            // Keep full send result alive to preserve router/temp_tag lifecycle.
-            let primary = paths.first().cloned().unwrap_or_else(|| PathBuf::from("."));
-            app_state.current_share = Some(ShareHandle::new(ticket.clone(), primary, result));
-            Ok(ticket)
-        }
-        Err(e) => {
-            let mut app_state = state.lock().await;
-            app_state.is_share_starting = false;
-            Err(e)
-        }
+/// Lease the persistent identity for a new transfer. While another transfer holds it, this one
+/// presents a random endpoint ID that contacts and allow-lists of other devices do not know,
+/// so the UI is told with `identity-fell-back`, like the relay fallback.
+fn lease_identity(
+    app_state: &AppState,
+    app_handle: &tauri::AppHandle,
+    direction: &str,
+) -> Option<IdentityLease> {
+    let lease = app_state.lease_identity();
+    if lease.is_none() && app_state.identity.is_some() {
+        tracing::warn!(
+            direction,
+            "identity in use by another transfer, using a random key"
+        );
+        let _ = app_handle.emit("identity-fell-back", direction);
     }
+    lease
 }
 
-async fn build_send_metadata(paths: &[PathBuf]) -> Result<FileMetadata, String> {
+/// How often live shares rescan their paths.
+const LIVE_SHARE_INTERVAL: Duration = Duration::from_secs(2);
+
+async fn build_send_metadata(
+    paths: &[PathBuf],
+    options: &SendOptions,
+) -> Result<FileMetadata, String> {
     if paths.is_empty() {
         return Err("No paths provided".to_string());
     }
 
-    let total_size = {
-        let paths_for_size = paths.to_vec();
-        tokio::task::spawn_blocking(move || {
-            let mut total = 0u64;
-            for path in &paths_for_size {
-                total = total.saturating_add(get_total_size(path)?);
-            }
-            Ok::<u64, String>(total)
-        })
+    let total_size = engine::shared_size(paths, options)
         .await
-        .map_err(|e| format!("Task join error: {}", e))??
-    };
+        .map_err(|e| format!("Failed to compute share size: {}", e))?;
 
     if paths.len() == 1 {
         let path = &paths[0];
@@ -405,6 +573,8 @@ async fn build_send_metadata(paths: &[PathBuf]) -> Result<FileMetadata, String>
             thumbnail,
             mime_type,
             items: None,
+            sender_name: None,
+            live: false,
         });
     }
 
@@ -414,7 +584,7 @@ async fn build_send_metadata(paths: &[PathBuf]) -> Result<FileMetadata, String>
         .unwrap_or_default()
         .to_string_lossy()
         .into_owned();
-    let preview_items = collect_preview_items(paths).await?;
+    let preview_items = collect_preview_items(paths, options).await?;
     let thumbnail = preview_items.iter().find_map(|item| item.thumbnail.clone());
 
     Ok(FileMetadata {
@@ -424,6 +594,8 @@ async fn build_send_metadata(paths: &[PathBuf]) -> Result<FileMetadata, String>
         thumbnail,
         mime_type: Some("application/x-iroh-collection".to_string()),
         items: Some(preview_items),
+        sender_name: None,
+        live: false,
     })
 }
 
@@ -432,16 +604,41 @@ async fn build_send_metadata(paths: &[PathBuf]) -> Result<FileMetadata, String>
 pub async fn fetch_ticket_metadata(
     ticket: String,
     relay: Option<RelayConfigArg>,
+    password: Option<String>,
+    state: State<'_, AppStateMutex>,
+    app_handle: tauri::AppHandle,
 ) -> Result<FileMetadata, String> {
     let ticket_len = ticket.len();
     tracing::info!(ticket_len, "fetch_ticket_metadata called");
 
+    let (identity_lease, contacts) = {
+        let app_state = state.lock().await;
+        (
+            lease_identity(&app_state, &app_handle, "receive"),
+            app_state.contacts.clone(),
+        )
+    };
+    let secret_key = identity_lease.as_ref().map(|l| l.secret_key().clone());
+    fetch_metadata_with(ticket, relay, secret_key, contacts, password).await
+}
+
+async fn fetch_metadata_with(
+    ticket: String,
+    relay: Option<RelayConfigArg>,
+    secret_key: Option<iroh::SecretKey>,
+    contacts: Option<AddressBook>,
+    password: Option<String>,
+) -> Result<FileMetadata, String> {
     let (relay_mode, _) = resolve_relay_mode_with_fallback(relay).await?;
     let options = ReceiveOptions {
         output_dir: None,
         relay_mode,
         magic_ipv4_addr: None,
         magic_ipv6_addr: None,
+        secret_key,
+        contacts,
+        password,
+        ..Default::default()
     };
 
     match fetch_metadata(ticket, options).await {
@@ -458,12 +655,17 @@ pub async fn fetch_ticket_metadata(
     }
 }
 
-/// Stop the current sharing session
+/// Stop a sharing session
 #[tauri::command]
-pub async fn stop_sharing(state: State<'_, AppStateMutex>) -> Result<(), String> {
-    let mut app_state = state.lock().await;
+pub async fn stop_sharing(share_id: String, state: State<'_, AppStateMutex>) -> Result<(), String> {
+    remove_share(&state, &share_id).await
+}
+
+/// Remove a share from the app state and stop it, which releases its identity lease
+async fn remove_share(state: &AppStateMutex, share_id: &str) -> Result<(), String> {
+    let share = state.lock().await.shares.remove(share_id);
 
-    if let Some(mut share) = app_state.current_share.take() {
+    if let Some(mut share) = share {
         // Explicitly clean up the share session
         if let Err(e) = share.stop().await {
             return Err(e);
@@ -476,55 +678,408 @@ pub async fn stop_sharing(state: State<'_, AppStateMutex>) -> Result<(), String>
     Ok(())
 }
 
-/// Receive a file using a ticket
+/// Register an in-flight receive so `cancel_receive`, `pause_receive` and the like can reach it
+async fn track_receive(
+    state: &AppStateMutex,
+    ticket: &str,
+    max_rate: Option<u64>,
+) -> Result<ActiveReceive, String> {
+    let mut app_state = state.lock().await;
+    if app_state.active_receives.contains_key(ticket) {
+        return Err("Already receiving this ticket".to_string());
+    }
+    let receive = ActiveReceive {
+        cancel_token: CancellationToken::new(),
+        control: ReceiveControl::new(),
+    };
+    receive.control.set_rate_limit(max_rate);
+    app_state
+        .active_receives
+        .insert(ticket.to_string(), receive.clone());
+    Ok(receive)
+}
+
+/// The handles of an in-flight receive
+async fn active_receive(state: &AppStateMutex, ticket: &str) -> Result<ActiveReceive, String> {
+    state
+        .lock()
+        .await
+        .active_receives
+        .get(ticket)
+        .cloned()
+        .ok_or_else(|| "No active receive for this ticket".to_string())
+}
+
+/// Optional settings of `receive_file` and `resume_receive`. Missing fields take their defaults.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReceiveOptionsArg {
+    pub conflict_policy: ConflictPolicy,
+    /// Only fetch the matching collection entries.
+    pub selection: Vec<EntrySelector>,
+    /// Only fetch the entries that differ from an earlier copy in the output directory.
+    pub mirror: Option<MirrorOptions>,
+    /// Receive the current version of a live share, see `FileMetadata::live`.
+    pub latest: bool,
+    pub password: Option<String>,
+    /// Download rate cap in bytes per second, see `set_receive_rate_limit`.
+    pub max_rate: Option<u64>,
+}
+
+/// Receive a file using a ticket, with the settings in `options`, see `ReceiveOptionsArg`.
 #[tauri::command]
 pub async fn receive_file(
     ticket: String,
     output_path: String,
     relay: Option<RelayConfigArg>,
+    options: Option<ReceiveOptionsArg>,
+    state: State<'_, AppStateMutex>,
     app_handle: tauri::AppHandle,
-) -> Result<String, String> {
-    // Create receive options with user-specified output path
-    let output_dir = PathBuf::from(output_path);
-    let (relay_mode, fell_back_to_public) = resolve_relay_mode_with_fallback(relay).await?;
-    if fell_back_to_public {
-        // Surface the silent custom->public fallback so the user knows this
-        // transfer is riding public relays despite their custom config.
-        let _ = app_handle.emit("relay-fell-back", "receive");
-    }
-    let options = ReceiveOptions {
-        output_dir: Some(output_dir),
-        relay_mode,
-        magic_ipv4_addr: None,
-        magic_ipv6_addr: None,
-    };
+) -> Result<ReceiveResult, String> {
+    run_receive(
+        ticket,
+        Some(PathBuf::from(output_path)),
+        relay,
+        options.unwrap_or_default(),
+        false,
+        &state,
+        app_handle,
+    )
+    .await
+}
 
-    // Wrap the app_handle in our EventEmitter implementation
-    let emitter = Arc::new(TauriEventEmitter {
-        app_handle: app_handle.clone(),
-    });
-    let boxed_handle: AppHandle = Some(emitter);
+/// Shared body of `receive_file` and `resume_receive`, which `resume` tells apart
+async fn run_receive(
+    ticket: String,
+    output_dir: Option<PathBuf>,
+    relay: Option<RelayConfigArg>,
+    options: ReceiveOptionsArg,
+    resume: bool,
+    state: &AppStateMutex,
+    app_handle: tauri::AppHandle,
+) -> Result<ReceiveResult, String> {
+    let ActiveReceive {
+        cancel_token,
+        control,
+    } = track_receive(state, &ticket, options.max_rate).await?;
+    let (identity_lease, contacts, conflict_decisions) = {
+        let app_state = state.lock().await;
+        (
+            lease_identity(&app_state, &app_handle, "receive"),
+            app_state.contacts.clone(),
+            app_state.conflict_decisions.clone(),
+        )
+    };
 
-    // Download using the core library
-    match download(ticket, options, boxed_handle).await {
-        Ok(result) => Ok(result.message),
-        Err(e) => {
-            tracing::error!("Failed to receive file: {}", e);
-            Err(format!("Failed to receive file: {}", e))
+    let result = async {
+        let (relay_mode, fell_back_to_public) = resolve_relay_mode_with_fallback(relay).await?;
+        if fell_back_to_public {
+            // Surface the silent custom->public fallback so the user knows this
+            // transfer is riding public relays despite their custom config.
+            let _ = app_handle.emit("relay-fell-back", "receive");
         }
+        let receive_options = ReceiveOptions {
+            output_dir,
+            relay_mode,
+            magic_ipv4_addr: None,
+            magic_ipv6_addr: None,
+            cancel_token,
+            control,
+            secret_key: identity_lease.as_ref().map(|l| l.secret_key().clone()),
+            contacts,
+            conflict_policy: options.conflict_policy,
+            conflict_decisions,
+            selection: options.selection,
+            mirror: options.mirror,
+            latest: options.latest,
+            password: options.password,
+        };
+
+        // Wrap the app_handle in our EventEmitter implementation
+        let emitter = Arc::new(ReceiveEventEmitter {
+            app_handle: app_handle.clone(),
+            ticket: ticket.clone(),
+        });
+        let boxed_handle: AppHandle = Some(emitter);
+
+        // Download using the core library
+        let (result, action) = if resume {
+            (
+                resume_download(ticket.clone(), receive_options, boxed_handle).await,
+                "resume receive",
+            )
+        } else {
+            (
+                download(ticket.clone(), receive_options, boxed_handle).await,
+                "receive file",
+            )
+        };
+        result.map_err(|e| {
+            tracing::error!("Failed to {}: {}", action, e);
+            format!("Failed to {}: {}", action, e)
+        })
+    }
+    .await;
+
+    state.lock().await.active_receives.remove(&ticket);
+    result
+}
+
+/// Cancel an in-flight receive. Partial data is kept so the receive can be resumed later.
+#[tauri::command]
+pub async fn cancel_receive(ticket: String, state: State<'_, AppStateMutex>) -> Result<(), String> {
+    active_receive(&state, &ticket).await?.cancel_token.cancel();
+    Ok(())
+}
+
+/// Pause an in-flight receive, keeping its connection and the data received so far.
+/// The receive emits `receive-paused` once it stopped fetching.
+#[tauri::command]
+pub async fn pause_receive(ticket: String, state: State<'_, AppStateMutex>) -> Result<(), String> {
+    active_receive(&state, &ticket).await?.control.pause();
+    Ok(())
+}
+
+/// Continue a receive paused with `pause_receive`, emitting `receive-resumed`
+#[tauri::command]
+pub async fn continue_receive(
+    ticket: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    active_receive(&state, &ticket).await?.control.resume();
+    Ok(())
+}
+
+/// Change the download rate cap of an in-flight receive, `None` for no limit
+#[tauri::command]
+pub async fn set_receive_rate_limit(
+    ticket: String,
+    max_rate: Option<u64>,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    active_receive(&state, &ticket)
+        .await?
+        .control
+        .set_rate_limit(max_rate);
+    Ok(())
+}
+
+/// Answer an `export-conflict` event of a receive running with `ConflictPolicy::Ask`
+#[tauri::command]
+pub async fn resolve_conflict(
+    id: u64,
+    decision: ConflictDecision,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    let app_state = state.lock().await;
+    if app_state.conflict_decisions.resolve(id, decision) {
+        Ok(())
+    } else {
+        Err("No pending conflict with this ID".to_string())
     }
 }
 
-/// Get the current sharing status
+/// Accept or deny a receiver announced by a `connection-request` event
+#[tauri::command]
+pub async fn respond_connection_request(
+    id: u64,
+    accept: bool,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    let app_state = state.lock().await;
+    if app_state.connection_decisions.resolve(id, accept) {
+        Ok(())
+    } else {
+        Err("No pending connection request with this ID".to_string())
+    }
+}
+
+/// List receives that were interrupted and still have partial data on disk
+#[tauri::command]
+pub async fn list_interrupted_receives() -> Result<Vec<InterruptedDownload>, String> {
+    list_interrupted_downloads()
+        .await
+        .map_err(|e| format!("Failed to list interrupted receives: {}", e))
+}
+
+/// Resume an interrupted receive, fetching only the data that is still missing.
+/// Without `output_path` the output directory of the first attempt is used, and without
+/// `options.selection` or `options.mirror` the ones of the first attempt.
+#[tauri::command]
+pub async fn resume_receive(
+    ticket: String,
+    output_path: Option<String>,
+    relay: Option<RelayConfigArg>,
+    options: Option<ReceiveOptionsArg>,
+    state: State<'_, AppStateMutex>,
+    app_handle: tauri::AppHandle,
+) -> Result<ReceiveResult, String> {
+    run_receive(
+        ticket,
+        output_path.map(PathBuf::from),
+        relay,
+        options.unwrap_or_default(),
+        true,
+        &state,
+        app_handle,
+    )
+    .await
+}
+
+/// Drop the partial data of an interrupted receive
 #[tauri::command]
-pub async fn get_sharing_status(state: State<'_, AppStateMutex>) -> Result<Option<String>, String> {
+pub async fn discard_interrupted_receive(hash: String) -> Result<(), String> {
+    discard_interrupted_download(&hash)
+        .await
+        .map_err(|e| format!("Failed to discard interrupted receive: {}", e))
+}
+
+/// Get the ticket of a share, or `None` if it is no longer active
+#[tauri::command]
+pub async fn get_sharing_status(
+    share_id: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<Option<String>, String> {
     let app_state = state.lock().await;
     Ok(app_state
-        .current_share
-        .as_ref()
+        .shares
+        .get(&share_id)
         .map(|share| share.ticket.clone()))
 }
 
+/// Snapshot of everyone who is or was downloading a share, as also sent by `receiver-progress`
+#[tauri::command]
+pub async fn list_share_receivers(
+    share_id: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<Vec<ReceiverProgress>, String> {
+    let app_state = state.lock().await;
+    app_state
+        .shares
+        .get(&share_id)
+        .map(|share| share.send_result.receivers.snapshot())
+        .ok_or_else(|| "No active share with this ID".to_string())
+}
+
+/// Change the upload rate limits of an active share, effective from the next chunk of every
+/// open connection.
+#[tauri::command]
+pub async fn set_share_rate_limits(
+    share_id: String,
+    limits: RateLimits,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    let app_state = state.lock().await;
+    let share = app_state
+        .shares
+        .get(&share_id)
+        .ok_or_else(|| "No active share with this ID".to_string())?;
+    share.send_result.rate_limiter.set_limits(limits);
+    tracing::info!(share_id = %share_id, ?limits, "share rate limits changed");
+    Ok(())
+}
+
+/// List all active shares
+#[tauri::command]
+pub async fn list_shares(state: State<'_, AppStateMutex>) -> Result<Vec<ShareStarted>, String> {
+    let app_state = state.lock().await;
+    let mut shares: Vec<ShareStarted> = app_state
+        .shares
+        .iter()
+        .map(|(share_id, share)| ShareStarted {
+            share_id: share_id.clone(),
+            info: share.send_result.info(),
+        })
+        .collect();
+    shares.sort_by(|a, b| a.share_id.cmp(&b.share_id));
+    Ok(shares)
+}
+
+/// Endpoint ID of the persistent identity. Shares started while no other transfer is
+/// running use it, so receivers can recognise this device.
+#[tauri::command]
+pub async fn get_endpoint_id(state: State<'_, AppStateMutex>) -> Result<String, String> {
+    let app_state = state.lock().await;
+    let identity = app_state
+        .identity
+        .as_ref()
+        .ok_or_else(|| "Identity not available".to_string())?;
+    Ok(identity.endpoint_id().to_string())
+}
+
+/// Replace the identity key with a new one and return the new endpoint ID.
+/// Fails while a share or receive is using the current key.
+#[tauri::command]
+pub async fn rotate_identity(state: State<'_, AppStateMutex>) -> Result<String, String> {
+    let mut app_state = state.lock().await;
+    let identity = app_state
+        .identity
+        .as_mut()
+        .ok_or_else(|| "Identity not available".to_string())?;
+    let endpoint_id = identity
+        .rotate()
+        .map_err(|e| format!("Failed to rotate identity: {}", e))?;
+    tracing::info!(%endpoint_id, "identity rotated");
+    Ok(endpoint_id.to_string())
+}
+
+/// Export the identity secret key as hex, e.g. to reuse it with the CLI via `IROH_SECRET`.
+#[tauri::command]
+pub async fn export_identity(state: State<'_, AppStateMutex>) -> Result<String, String> {
+    let app_state = state.lock().await;
+    let identity = app_state
+        .identity
+        .as_ref()
+        .ok_or_else(|| "Identity not available".to_string())?;
+    Ok(identity.export_secret())
+}
+
+/// List known peers, ordered by name
+#[tauri::command]
+pub async fn list_contacts(state: State<'_, AppStateMutex>) -> Result<Vec<Contact>, String> {
+    let app_state = state.lock().await;
+    Ok(app_state
+        .contacts
+        .as_ref()
+        .map(AddressBook::list)
+        .unwrap_or_default())
+}
+
+/// Add a peer to the address book, or rename it if it is already known
+#[tauri::command]
+pub async fn add_contact(
+    endpoint_id: String,
+    name: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<Contact, String> {
+    let mut app_state = state.lock().await;
+    let contacts = app_state
+        .contacts
+        .as_mut()
+        .ok_or_else(|| "Address book not available".to_string())?;
+    contacts
+        .add(&endpoint_id, &name)
+        .map_err(|e| format!("Failed to add contact: {}", e))
+}
+
+/// Remove a peer from the address book
+#[tauri::command]
+pub async fn remove_contact(
+    endpoint_id: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<(), String> {
+    let mut app_state = state.lock().await;
+    let contacts = app_state
+        .contacts
+        .as_mut()
+        .ok_or_else(|| "Address book not available".to_string())?;
+    match contacts.remove(&endpoint_id) {
+        Ok(true) => Ok(()),
+        Ok(false) => Err("Unknown contact".to_string()),
+        Err(e) => Err(format!("Failed to remove contact: {}", e)),
+    }
+}
+
 /// Check if a path is a file or directory
 #[tauri::command]
 pub async fn check_path_type(path: String) -> Result<String, String> {
@@ -567,11 +1122,18 @@ pub async fn get_paths_mime_types(paths: Vec<String>) -> Result<Vec<Option<Strin
     Ok(result)
 }
 
-/// Get the current transport status (whether bytes are actively being transferred)
+/// Get the transport status of a share (whether bytes are actively being transferred)
 #[tauri::command]
-pub async fn get_transport_status(state: State<'_, AppStateMutex>) -> Result<bool, String> {
+pub async fn get_transport_status(
+    share_id: String,
+    state: State<'_, AppStateMutex>,
+) -> Result<bool, String> {
     let app_state = state.lock().await;
-    Ok(app_state.is_transporting)
+    app_state
+        .shares
+        .get(&share_id)
+        .map(|share| share.is_transporting.load(Ordering::SeqCst))
+        .ok_or_else(|| "No active share with this ID".to_string())
 }
 
 /// Check if there was a launch intent (file path passed via CLI)
@@ -603,35 +1165,11 @@ pub async fn toggle_context_menu(enable: bool) -> Result<(), String> {
     }
 }
 
-/// Helper function to calculate total size of a file or directory
-fn get_total_size(path: &Path) -> Result<u64, String> {
-    if path.is_file() {
-        return std::fs::metadata(path)
-            .map(|m| m.len())
-            .map_err(|e| format!("Failed to read metadata for {}: {e}", path.display()));
-    }
-
-    if path.is_dir() {
-        let mut total_size = 0u64;
-        for entry in walkdir::WalkDir::new(path) {
-            let entry = entry.map_err(|e| format!("Failed to traverse {}: {e}", path.display()))?;
-            if entry.file_type().is_file() {
-                let metadata = entry.metadata().map_err(|e| {
-                    format!(
-                        "Failed to read metadata for {}: {e}",
-                        entry.path().display()
-                    )
-                })?;
-                total_size = total_size.saturating_add(metadata.len());
-            }
-        }
-        return Ok(total_size);
-    }
-
-    Err(format!(
-        "Path is neither a file nor a directory: {}",
-        path.display()
-    ))
+/// Helper function to calculate the shared size of a file or directory
+async fn get_total_size(path: &Path, options: &SendOptions) -> Result<u64, String> {
+    engine::shared_size(&[path.to_path_buf()], options)
+        .await
+        .map_err(|e| format!("Failed to compute size of {}: {e}", path.display()))
 }
 
 fn dedup_name(name: &str, seen: &mut BTreeMap<String, usize>) -> String {
@@ -647,7 +1185,10 @@ fn dedup_name(name: &str, seen: &mut BTreeMap<String, usize>) -> String {
     }
 }
 
-async fn collect_preview_items(paths: &[PathBuf]) -> Result<Vec<FilePreviewItem>, String> {
+async fn collect_preview_items(
+    paths: &[PathBuf],
+    options: &SendOptions,
+) -> Result<Vec<FilePreviewItem>, String> {
     let mut items = Vec::with_capacity(paths.len());
     let mut seen_names = BTreeMap::new();
 
@@ -659,7 +1200,7 @@ async fn collect_preview_items(paths: &[PathBuf]) -> Result<Vec<FilePreviewItem>
             .unwrap_or("item")
             .to_string();
         let final_name = dedup_name(&file_name, &mut seen_names);
-        let size = get_total_size(path)?;
+        let size = get_total_size(path, options).await?;
         let mime_type = if path.is_dir() {
             Some("inode/directory".to_string())
         } else {
@@ -802,6 +1343,8 @@ mod tests {
             thumbnail: Some("data:image/jpeg;base64,ZmFrZS10aHVtYg==".to_string()),
             mime_type: Some("text/plain".to_string()),
             items: None,
+            sender_name: None,
+            live: false,
         };
 
         let options = SendOptions {
@@ -809,6 +1352,7 @@ mod tests {
             ticket_type: AddrInfoOptions::RelayAndAddresses,
             magic_ipv4_addr: None,
             magic_ipv6_addr: None,
+            ..Default::default()
         };
 
         let share = start_share(
@@ -820,7 +1364,7 @@ mod tests {
         .await
         .expect("start_share should succeed");
 
-        let fetched = fetch_ticket_metadata(share.ticket.clone(), None)
+        let fetched = fetch_metadata_with(share.ticket.clone(), None, None, None, None)
             .await
             .expect("fetch_ticket_metadata command should succeed");
 
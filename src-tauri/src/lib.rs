@@ -19,7 +19,9 @@ use std::sync::Arc;
 use tauri::Emitter as _;
 use tauri::Manager as _;
 
-/// Clean up any orphaned .sendme-* directories from previous runs
+/// Clean up any orphaned .sendme-* directories from previous runs.
+/// Interrupted receives live in the engine's partial downloads directory and are left alone
+/// so they can be resumed; `.sendme-recv-*` here only matches the old temp dir layout.
 fn cleanup_orphaned_directories() {
     let scan_dirs = vec![std::env::current_dir().ok(), Some(std::env::temp_dir())];
     for base_dir in scan_dirs.into_iter().flatten() {
@@ -83,7 +85,25 @@ pub fn run() {
             send_items,
             stop_sharing,
             receive_file,
+            cancel_receive,
+            pause_receive,
+            continue_receive,
+            set_receive_rate_limit,
+            resolve_conflict,
+            respond_connection_request,
+            list_interrupted_receives,
+            resume_receive,
+            discard_interrupted_receive,
             get_sharing_status,
+            list_shares,
+            list_share_receivers,
+            set_share_rate_limits,
+            get_endpoint_id,
+            rotate_identity,
+            export_identity,
+            list_contacts,
+            add_contact,
+            remove_contact,
             check_path_type,
             get_paths_mime_types,
             get_transport_status,
@@ -148,9 +168,62 @@ fn app_state_initial() -> AppState {
     }
 }
 
+/// Log receives that were interrupted in a previous run; the UI lists them via `list_interrupted_receives`.
+fn scan_interrupted_receives() {
+    tauri::async_runtime::spawn(async {
+        match engine::list_interrupted_downloads().await {
+            Ok(interrupted) if !interrupted.is_empty() => {
+                tracing::info!(
+                    count = interrupted.len(),
+                    "found interrupted receives that can be resumed"
+                );
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to scan interrupted receives: {}", e),
+        }
+    });
+}
+
+/// Load the persistent identity and the address book from the app data dir, next to the app store.
+/// Without an identity every share falls back to a random key.
+fn load_identity_and_contacts(app: &tauri::App) {
+    let data_dir = match app.path().app_data_dir() {
+        Ok(dir) => dir,
+        Err(e) => {
+            tracing::warn!("No app data dir for identity and contacts: {}", e);
+            return;
+        }
+    };
+    let identity = match engine::Identity::load_or_create(data_dir.join("identity.key")) {
+        Ok(identity) => {
+            tracing::info!(endpoint_id = %identity.endpoint_id(), "identity loaded");
+            Some(identity)
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load identity: {}", e);
+            None
+        }
+    };
+    let contacts = match engine::AddressBook::load(data_dir.join("contacts.json")) {
+        Ok(contacts) => Some(contacts),
+        Err(e) => {
+            tracing::warn!("Failed to load address book: {}", e);
+            None
+        }
+    };
+    let state = app.state::<state::AppStateMutex>().inner().clone();
+    tauri::async_runtime::spawn(async move {
+        let mut app_state = state.lock().await;
+        app_state.identity = identity;
+        app_state.contacts = contacts;
+    });
+}
+
 #[allow(unused_variables)]
 fn setup_common(app: &tauri::App) {
     cleanup_orphaned_directories();
+    scan_interrupted_receives();
+    load_identity_and_contacts(app);
     tracing::debug!("File drop support enabled via dragDropEnabled config");
 
     #[cfg(target_os = "linux")]
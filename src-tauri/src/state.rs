@@ -1,23 +1,56 @@
-use engine::SendResult;
+use engine::{
+    AddressBook, CancellationToken, ConflictDecision, Identity, IdentityLease, PendingDecisions,
+    ReceiveControl, SendResult,
+};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
 /// Application state for managing sharing sessions
 #[derive(Default)]
 pub struct AppState {
-    pub current_share: Option<ShareHandle>,
-    pub is_share_starting: bool, // True while start_sharing is preparing metadata/session
-    pub is_transporting: bool,   // True when actual data transfer is happening
+    pub shares: HashMap<String, ShareHandle>, // Active shares keyed by share ID
+    pub next_share_id: u64,
     pub launch_intent: Option<String>, // Path to file/folder passed via CLI (e.g. context menu)
+    pub active_receives: HashMap<String, ActiveReceive>, // In-flight downloads keyed by ticket
+    pub identity: Option<Identity>,    // Persistent endpoint key, loaded at startup
+    pub contacts: Option<AddressBook>, // Known peers, loaded at startup
+    pub conflict_decisions: PendingDecisions<ConflictDecision>, // Answers to `export-conflict` events
+    pub connection_decisions: PendingDecisions<bool>, // Answers to `connection-request` events
+}
+
+impl AppState {
+    /// Allocate an ID for a new share. IDs are never reused within a run.
+    pub fn allocate_share_id(&mut self) -> String {
+        self.next_share_id += 1;
+        format!("share-{}", self.next_share_id)
+    }
+
+    /// Lease the persistent identity for a new endpoint.
+    /// `None` means another share or receive holds it, and the endpoint uses a random key instead;
+    /// commands report that to the UI as `identity-fell-back`.
+    pub fn lease_identity(&self) -> Option<IdentityLease> {
+        self.identity.as_ref().and_then(Identity::try_lease)
+    }
+}
+
+/// Handles through which the app cancels, pauses and paces an in-flight receive
+#[derive(Clone)]
+pub struct ActiveReceive {
+    pub cancel_token: CancellationToken,
+    pub control: ReceiveControl,
 }
 
 /// Handle for an active sharing session
 /// CRITICAL: This struct holds the router and temp_tag which keeps the server alive
 pub struct ShareHandle {
     pub ticket: String,
-    pub _path: PathBuf,          // Keep path for potential future use
-    pub send_result: SendResult, // This keeps router and temp_tag alive!
+    pub _path: PathBuf,                   // Keep path for potential future use
+    pub send_result: SendResult,          // This keeps router and temp_tag alive!
+    pub is_transporting: Arc<AtomicBool>, // True when actual data transfer is happening
+    pub identity_lease: Option<IdentityLease>, // Released by `stop` once the endpoint is closed
 }
 
 impl Drop for ShareHandle {
@@ -43,11 +76,19 @@ impl Drop for ShareHandle {
 }
 
 impl ShareHandle {
-    pub fn new(ticket: String, path: PathBuf, send_result: SendResult) -> Self {
+    pub fn new(
+        ticket: String,
+        path: PathBuf,
+        send_result: SendResult,
+        is_transporting: Arc<AtomicBool>,
+        identity_lease: Option<IdentityLease>,
+    ) -> Self {
         Self {
             ticket,
             _path: path,
             send_result,
+            is_transporting,
+            identity_lease,
         }
     }
 
@@ -73,6 +114,9 @@ impl ShareHandle {
         let endpoint = self.send_result.router.endpoint();
         endpoint.close().await;
 
+        // Only now may another endpoint take over the identity without fighting over the relay
+        self.identity_lease.take();
+
         // temp_tag, _store, and _progress_handle will be dropped automatically when the method ends
         // Cleanup of blobs directory will happen in Drop trait
 
@@ -96,6 +96,13 @@ function TicketPreviewCard({
 					<p className="text-xs text-muted-foreground">
 						{formatFileSize(previewMetadata.size)}
 					</p>
+					{previewMetadata.senderName ? (
+						<p className="text-xs text-muted-foreground truncate">
+							{t('common:receiver.previewFrom', {
+								name: previewMetadata.senderName,
+							})}
+						</p>
+					) : null}
 				</div>
 				{canExpandPreviewList ? (
 					<Button
@@ -0,0 +1 @@
+export { SharingSettings } from './sharing-settings'
@@ -0,0 +1,18 @@
+import { useTranslation } from '../../../i18n'
+import { Frame, FrameHeader, FramePanel, FrameTitle } from '../../ui/frame'
+import { UseIgnoreFilesToggle } from './use-ignore-files-toggle'
+
+export function SharingSettings() {
+	const { t } = useTranslation()
+
+	return (
+		<Frame>
+			<FrameHeader>
+				<FrameTitle>{t('settings.general.sharing.title')}</FrameTitle>
+			</FrameHeader>
+			<FramePanel>
+				<UseIgnoreFilesToggle />
+			</FramePanel>
+		</Frame>
+	)
+}
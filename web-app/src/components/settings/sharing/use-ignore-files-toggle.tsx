@@ -0,0 +1,26 @@
+import { useTranslation } from '../../../i18n'
+import { useAppSettingStore } from '../../../store/app-setting'
+import { FrameDescription, FrameTitle } from '../../ui/frame'
+import { Switch } from '../../ui/switch'
+
+export function UseIgnoreFilesToggle() {
+	const { t } = useTranslation()
+	const useIgnoreFiles = useAppSettingStore((state) => state.useIgnoreFiles)
+	const setUseIgnoreFiles = useAppSettingStore(
+		(state) => state.setUseIgnoreFiles
+	)
+
+	return (
+		<div className="flex items-center justify-between">
+			<div className="flex-1">
+				<FrameTitle>
+					{t('settings.general.sharing.ignoreFiles.label')}
+				</FrameTitle>
+				<FrameDescription>
+					{t('settings.general.sharing.ignoreFiles.description')}
+				</FrameDescription>
+			</div>
+			<Switch checked={useIgnoreFiles} onCheckedChange={setUseIgnoreFiles} />
+		</div>
+	)
+}
@@ -7,6 +7,14 @@ import { selectDownloadFolder } from '@/plugins/nativeUtils'
 import { useCallback, useEffect, useRef, useState } from 'react'
 import { useTranslation } from '../i18n/react-i18next-compat'
 import { sendSystemNotification } from '../lib/systemNotification'
+import type {
+	ConflictsEvent,
+	ExportProgressEvent,
+	FileNamesEvent,
+	ProgressEvent,
+	ReceiveEvent,
+	ReceiveResult,
+} from '../lib/tauri'
 import type { AlertDialogState, AlertType } from '../types/ui'
 import type {
 	TicketPreviewMetadata,
@@ -32,6 +40,8 @@ interface BackendFileMetadata {
 				mime_type?: string | null
 		  }[]
 		| null
+	sender_name?: string | null
+	live?: boolean
 }
 
 const isAbsolutePath = (path: string) => {
@@ -125,6 +135,8 @@ export function useReceiver(): UseReceiverReturn {
 	const previewRequestSeqRef = useRef(0)
 	const previewMetadataRef = useRef<TicketPreviewMetadata | null>(null)
 	const transferItemCountRef = useRef<number | undefined>(undefined)
+	// Ticket of the receive started here; events of other receives are ignored.
+	const receiveTicketRef = useRef<string | null>(null)
 
 	const resolveRevealPath = async (basePath: string, names: string[]) => {
 		if (!basePath) return null
@@ -234,6 +246,8 @@ export function useReceiver(): UseReceiverReturn {
 						thumbnail: item.thumbnail ?? undefined,
 						mimeType: item.mime_type ?? undefined,
 					})),
+					senderName: payload.sender_name ?? undefined,
+					live: payload.live ?? false,
 				}
 				setPreviewMetadata(metadata)
 				previewMetadataRef.current = metadata
@@ -292,7 +306,13 @@ export function useReceiver(): UseReceiverReturn {
 			eventName: string,
 			handler: Parameters<typeof listen>[1]
 		) => {
-			const unlisten = await listen(eventName, handler)
+			// Receive events carry the ticket of the receive they belong to; several receives can
+			// run at once.
+			const unlisten = await listen<ReceiveEvent>(eventName, (event) => {
+				if (event.payload.ticket === receiveTicketRef.current) {
+					handler(event)
+				}
+			})
 			if (disposed) {
 				unlisten()
 				return
@@ -310,58 +330,56 @@ export function useReceiver(): UseReceiverReturn {
 			})
 
 			await registerListener('receive-progress', (event: any) => {
-				try {
-					const payload = event.payload as string
-					const parts = payload.split(':')
-
-					if (parts.length === 3) {
-						const bytesTransferred = parseInt(parts[0], 10)
-						const totalBytes = parseInt(parts[1], 10)
-						const speedInt = parseInt(parts[2], 10)
-						const speedBps = speedInt / 1000.0
-						const percentage =
-							totalBytes > 0
-								? Math.min((bytesTransferred / totalBytes) * 100, 100)
-								: 0
-
-						// Add speed sample and calculate ETA
-						speedAveragerRef.current.addSample(speedBps)
-						const avgSpeed = speedAveragerRef.current.getAverage()
-						const bytesRemaining = Math.max(totalBytes - bytesTransferred, 0)
-						const eta = calculateETA(bytesRemaining, avgSpeed)
-
-						setTransferProgress({
-							bytesTransferred,
-							totalBytes,
-							speedBps,
-							percentage,
-							etaSeconds: eta ?? undefined,
-						})
-					}
-				} catch (error) {
-					console.error('Failed to parse progress event:', error)
-				}
+				const { bytesTransferred, totalBytes, speedBps } =
+					event.payload as ProgressEvent
+				const percentage =
+					totalBytes > 0
+						? Math.min((bytesTransferred / totalBytes) * 100, 100)
+						: 0
+
+				// Add speed sample and calculate ETA
+				speedAveragerRef.current.addSample(speedBps)
+				const avgSpeed = speedAveragerRef.current.getAverage()
+				const bytesRemaining = Math.max(totalBytes - bytesTransferred, 0)
+				const eta = calculateETA(bytesRemaining, avgSpeed)
+
+				setTransferProgress({
+					bytesTransferred,
+					totalBytes,
+					speedBps,
+					percentage,
+					etaSeconds: eta ?? undefined,
+				})
 			})
 
-			await registerListener('receive-file-names', (event: any) => {
-				try {
-					const payload = event.payload as string
-					const names = JSON.parse(payload) as string[]
+			await registerListener('export-progress', (event: any) => {
+				const payload = event.payload as ExportProgressEvent
+				const percentage =
+					payload.totalBytes > 0
+						? Math.min((payload.bytesCopied / payload.totalBytes) * 100, 100)
+						: 100
+
+				setTransferProgress({
+					bytesTransferred: payload.bytesCopied,
+					totalBytes: payload.totalBytes,
+					speedBps: 0,
+					percentage,
+					scope: 'file',
+					currentFileName: payload.fileName,
+					fileIndex: payload.fileIndex,
+					totalFiles: payload.totalFiles,
+				})
+			})
 
-					setFileNames(names)
-					fileNamesRef.current = names
-				} catch (error) {
-					console.error('Failed to parse file names event:', error)
-				}
+			await registerListener('receive-file-names', (event: any) => {
+				const { names } = event.payload as FileNamesEvent
+				setFileNames(names)
+				fileNamesRef.current = names
 			})
 
 			await registerListener('receive-conflicts', (event: any) => {
 				try {
-					const payload = event.payload as string
-					const conflicts = JSON.parse(payload) as Array<{
-						original: string
-						resolved: string
-					}>
+					const { conflicts } = event.payload as ConflictsEvent
 
 					if (conflicts.length === 0) return
 
@@ -369,7 +387,11 @@ export function useReceiver(): UseReceiverReturn {
 						normalizeSeparators(p).split('/').pop() || p
 					const preview = conflicts
 						.slice(0, 3)
-						.map((c) => `${basename(c.original)} → ${basename(c.resolved)}`)
+						.map((c) =>
+							c.action === 'renamed'
+								? `${basename(c.original)} → ${basename(c.resolved)}`
+								: `${basename(c.original)}: ${t(`common:receiver.conflictAction.${c.action}`)}`
+						)
 						.join('\n')
 
 					pendingConflictNoticeRef.current =
@@ -509,6 +531,8 @@ export function useReceiver(): UseReceiverReturn {
 
 		try {
 			transferItemCountRef.current = previewMetadata?.itemCount
+			// A live share is received in its current version, not the one of the ticket.
+			const latest = previewMetadata?.live ?? false
 			previewRequestSeqRef.current += 1
 			setIsReceiving(true)
 			setIsTransporting(false)
@@ -520,11 +544,13 @@ export function useReceiver(): UseReceiverReturn {
 			setIsPreviewLoading(false)
 			pendingConflictNoticeRef.current = null
 			folderOpenTriggeredRef.current = false
+			receiveTicketRef.current = ticket.trim()
 
-			await invoke<string>('receive_file', {
+			await invoke<ReceiveResult>('receive_file', {
 				ticket: ticket.trim(),
 				outputPath: savePath,
 				relay: getRelayConfigArg(),
+				options: { latest },
 			})
 		} catch (error) {
 			console.error('Failed to receive file:', error)
@@ -550,6 +576,7 @@ export function useReceiver(): UseReceiverReturn {
 		pendingConflictNoticeRef.current = null
 		folderOpenTriggeredRef.current = false
 		transferItemCountRef.current = undefined
+		receiveTicketRef.current = null
 	}
 
 	const handleOpenFolder = async () => {
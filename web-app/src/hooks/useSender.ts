@@ -6,8 +6,21 @@ import type { AlertType } from '../types/ui'
 import type { TransferMetadata, TransferProgress } from '../types/transfer'
 import { SpeedAverager, calculateETA } from '../utils/etaUtils'
 import { getRelayConfigArg } from '../lib/relay'
+import { useAppSettingStore } from '../store/app-setting'
+import type {
+	ActiveConnectionCountEvent,
+	ProgressEvent,
+	ShareEvent,
+	ShareExpiredEvent,
+	ShareStarted,
+} from '../lib/tauri'
 import { useSenderStore } from '../store/sender-store'
 
+// Share settings passed to `send_items`, and to `get_file_size` so sizes match the share.
+function getShareOptionsArg() {
+	return { useIgnoreFiles: useAppSettingStore.getState().useIgnoreFiles }
+}
+
 export interface UseSenderReturn {
 	// View state (replaces isSharing, isTransporting, isCompleted)
 	viewState: 'IDLE' | 'SHARING' | 'TRANSPORTING' | 'SUCCESS'
@@ -53,6 +66,7 @@ export function useSender(): UseSenderReturn {
 	// Get store state and actions
 	const {
 		viewState,
+		shareId,
 		ticket,
 		selectedPaths,
 		selectedPath,
@@ -65,6 +79,7 @@ export function useSender(): UseSenderReturn {
 		isBroadcastMode,
 		activeConnectionCount,
 		setViewState,
+		setShareId,
 		setTicket,
 		setSelectedPaths,
 		addSelectedPaths,
@@ -107,12 +122,17 @@ export function useSender(): UseSenderReturn {
 	}, [pathType])
 
 	useEffect(() => {
+		// Share events carry the ID of the share they belong to; several shares can be live at once.
+		const isCurrentShare = (event: any) =>
+			event.payload?.shareId === useSenderStore.getState().shareId
+
 		let disposed = false
 		let unlistenStart: UnlistenFn | undefined
 		let unlistenProgress: UnlistenFn | undefined
 		let unlistenComplete: UnlistenFn | undefined
 		let unlistenFailed: UnlistenFn | undefined
 		let unlistenActiveCount: UnlistenFn | undefined
+		let unlistenExpired: UnlistenFn | undefined
 
 		const safeUnlisten = (unlisten?: UnlistenFn) => {
 			if (unlisten) {
@@ -121,30 +141,21 @@ export function useSender(): UseSenderReturn {
 		}
 
 		const setupListeners = async () => {
-			const nextUnlistenActiveCount = await listen(
-				'active-connection-count',
-				(event: any) => {
-					try {
-						const count = parseInt(event.payload as string, 10)
-						if (!Number.isNaN(count)) {
-							// console.log('[useSender] active-connection-count event received:', count)
-							setActiveConnectionCount(count)
-						}
-					} catch (error) {
-						console.error(
-							'Failed to parse active connection count event:',
-							error
-						)
-					}
-				}
-			)
+			const nextUnlistenActiveCount = await listen<
+				ShareEvent<ActiveConnectionCountEvent>
+			>('active-connection-count', (event) => {
+				if (!isCurrentShare(event)) return
+				// console.log('[useSender] active-connection-count event received:', event.payload.count)
+				setActiveConnectionCount(event.payload.count)
+			})
 			if (disposed) {
 				nextUnlistenActiveCount()
 			} else {
 				unlistenActiveCount = nextUnlistenActiveCount
 			}
 
-			const nextUnlistenStart = await listen('transfer-started', () => {
+			const nextUnlistenStart = await listen('transfer-started', (event) => {
+				if (!isCurrentShare(event)) return
 				const storeState = useSenderStore.getState()
 				// console.log('[useSender] transfer-started event received:', {
 				// 	currentViewState: storeState.viewState,
@@ -199,50 +210,39 @@ export function useSender(): UseSenderReturn {
 				unlistenStart = nextUnlistenStart
 			}
 
-			const nextUnlistenProgress = await listen(
+			const nextUnlistenProgress = await listen<ShareEvent<ProgressEvent>>(
 				'transfer-progress',
-				(event: any) => {
-					try {
-						const storeState = useSenderStore.getState()
-						const canAcceptProgress =
-							storeState.viewState === 'TRANSPORTING' ||
-							(storeState.isBroadcastMode && storeState.viewState === 'SHARING')
-						if (!canAcceptProgress) {
-							return
-						}
+				(event) => {
+					if (!isCurrentShare(event)) return
+					const storeState = useSenderStore.getState()
+					const canAcceptProgress =
+						storeState.viewState === 'TRANSPORTING' ||
+						(storeState.isBroadcastMode && storeState.viewState === 'SHARING')
+					if (!canAcceptProgress) {
+						return
+					}
 
-						const rawPayload = event.payload as string
-
-						const parts = rawPayload.split(':')
-
-						if (parts.length === 3) {
-							const bytesTransferred = parseInt(parts[0], 10)
-							const totalBytes = parseInt(parts[1], 10)
-							const speedRaw = Number.parseFloat(parts[2])
-							const speedBps = Number.isFinite(speedRaw)
-								? Math.max(speedRaw, 0)
-								: 0
-							const percentage =
-								totalBytes > 0
-									? Math.min((bytesTransferred / totalBytes) * 100, 100)
-									: 0
-
-							// Add speed sample and calculate ETA
-							speedAveragerRef.current.addSample(speedBps)
-							const avgSpeed = speedAveragerRef.current.getAverage()
-							const bytesRemaining = Math.max(totalBytes - bytesTransferred, 0)
-							const eta = calculateETA(bytesRemaining, avgSpeed)
-
-							latestProgressRef.current = {
-								bytesTransferred,
-								totalBytes,
-								speedBps,
-								percentage,
-								etaSeconds: eta ?? undefined,
-							}
-						}
-					} catch (error) {
-						console.error('Failed to parse progress event:', error)
+					const { bytesTransferred, totalBytes } = event.payload
+					const speedBps = Number.isFinite(event.payload.speedBps)
+						? Math.max(event.payload.speedBps, 0)
+						: 0
+					const percentage =
+						totalBytes > 0
+							? Math.min((bytesTransferred / totalBytes) * 100, 100)
+							: 0
+
+					// Add speed sample and calculate ETA
+					speedAveragerRef.current.addSample(speedBps)
+					const avgSpeed = speedAveragerRef.current.getAverage()
+					const bytesRemaining = Math.max(totalBytes - bytesTransferred, 0)
+					const eta = calculateETA(bytesRemaining, avgSpeed)
+
+					latestProgressRef.current = {
+						bytesTransferred,
+						totalBytes,
+						speedBps,
+						percentage,
+						etaSeconds: eta ?? undefined,
 					}
 				}
 			)
@@ -254,7 +254,8 @@ export function useSender(): UseSenderReturn {
 
 			const nextUnlistenComplete = await listen(
 				'transfer-completed',
-				async () => {
+				async (event) => {
+					if (!isCurrentShare(event)) return
 					const storeState = useSenderStore.getState()
 					// console.log('[useSender] transfer-completed event received:', {
 					// 	wasManuallyStopped: wasManuallyStoppedRef.current,
@@ -368,6 +369,7 @@ export function useSender(): UseSenderReturn {
 							if (shouldResolveExactSize) {
 								const fileSize = await invoke<number>('get_file_size', {
 									path: pathToUse,
+									options: getShareOptionsArg(),
 								})
 								// console.log('[useSender] transfer-completed: got file size, updating metadata:', {
 								// 	fileSize,
@@ -413,7 +415,8 @@ export function useSender(): UseSenderReturn {
 				unlistenComplete = nextUnlistenComplete
 			}
 
-			const nextUnlistenFailed = await listen('transfer-failed', async () => {
+			const nextUnlistenFailed = await listen('transfer-failed', async (event) => {
+				if (!isCurrentShare(event)) return
 				const storeState = useSenderStore.getState()
 				// console.log('[useSender] transfer-failed event received:', {
 				// 	wasManuallyStopped: wasManuallyStoppedRef.current,
@@ -511,6 +514,37 @@ export function useSender(): UseSenderReturn {
 			} else {
 				unlistenFailed = nextUnlistenFailed
 			}
+
+			// The share stopped on its own after its time or download limit ran out.
+			const nextUnlistenExpired = await listen<ShareEvent<ShareExpiredEvent>>(
+				'share-expired',
+				(event) => {
+					if (!isCurrentShare(event)) return
+					const storeState = useSenderStore.getState()
+					invoke('stop_sharing', { shareId: storeState.shareId }).catch(
+						(error) => {
+							console.warn('Background cleanup failed (non-critical):', error)
+						}
+					)
+					setShareId(null)
+					if (storeState.viewState === 'SHARING') {
+						setActiveConnectionCount(0)
+						resetToIdle()
+					}
+					showAlert(
+						t('common:sender.shareExpired'),
+						event.payload.reason === 'downloadLimit'
+							? t('common:sender.shareExpiredDownloadLimit')
+							: t('common:sender.shareExpiredTimeLimit'),
+						'info'
+					)
+				}
+			)
+			if (disposed) {
+				nextUnlistenExpired()
+			} else {
+				unlistenExpired = nextUnlistenExpired
+			}
 		}
 
 		setupListeners().catch((error) => {
@@ -528,18 +562,24 @@ export function useSender(): UseSenderReturn {
 			safeUnlisten(unlistenComplete)
 			safeUnlisten(unlistenFailed)
 			safeUnlisten(unlistenActiveCount)
+			safeUnlisten(unlistenExpired)
 			unlistenStart = undefined
 			unlistenProgress = undefined
 			unlistenComplete = undefined
 			unlistenFailed = undefined
 			unlistenActiveCount = undefined
+			unlistenExpired = undefined
 		}
 	}, [
 		setViewState,
+		setShareId,
 		setTransferMetadata,
 		setTransferProgress,
 		resetForBroadcast,
+		resetToIdle,
 		setActiveConnectionCount,
+		showAlert,
+		t,
 	])
 
 	const handleFilesSelect = async (
@@ -638,13 +678,38 @@ export function useSender(): UseSenderReturn {
 			speedAveragerRef.current.reset()
 
 			setIsLoading(true)
-			const result = await invoke<string>('send_items', {
-				paths: selectedPaths,
-				relay: getRelayConfigArg(),
-			})
+			const result = await invoke<ShareStarted>(
+				'send_items',
+				{
+					paths: selectedPaths,
+					relay: getRelayConfigArg(),
+					options: getShareOptionsArg(),
+				}
+			)
 			// console.log('[useSender] startSharing: got ticket, setting state to SHARING')
-			setTicket(result)
+			setShareId(result.shareId)
+			setTicket(result.ticket)
 			setViewState('SHARING')
+			if (result.skippedLinks.length > 0) {
+				showAlert(
+					t('common:sender.skippedLinks'),
+					t('common:sender.skippedLinksDesc', {
+						count: result.skippedLinks.length,
+						names: result.skippedLinks.map((link) => link.name).join(', '),
+					}),
+					'info'
+				)
+			}
+			if (result.skipped.length > 0) {
+				showAlert(
+					t('common:sender.skippedEntries'),
+					t('common:sender.skippedEntriesDesc', {
+						count: result.skipped.length,
+						names: result.skipped.map((entry) => entry.name).join(', '),
+					}),
+					'info'
+				)
+			}
 		} catch (error) {
 			console.error('[useSender] startSharing: failed:', error)
 			showAlert(
@@ -681,6 +746,7 @@ export function useSender(): UseSenderReturn {
 			// 	hasTransferMetadata: !!transferMetadata,
 			// })
 
+			const currentShareId = shareId
 			const currentSelectedPath = selectedPathRef.current
 			const currentTransferStartTime = transferStartTimeRef.current
 			const storeState = useSenderStore.getState()
@@ -741,13 +807,18 @@ export function useSender(): UseSenderReturn {
 				latestProgressRef.current = null
 				speedAveragerRef.current.reset()
 
-				invoke('stop_sharing').catch((error) => {
-					console.warn('Background cleanup failed (non-critical):', error)
-				})
+				invoke('stop_sharing', { shareId: currentShareId }).catch(
+					(error) => {
+						console.warn('Background cleanup failed (non-critical):', error)
+					}
+				)
 				return
 			}
 
-			await invoke('stop_sharing')
+			if (currentShareId) {
+				await invoke('stop_sharing', { shareId: currentShareId })
+			}
+			setShareId(null)
 
 			// If no active transfer (just sharing, waiting for acceptance), reset to idle
 			if (!wasActiveTransfer || !currentSelectedPath) {
@@ -17,6 +17,7 @@ export const defaultAppSettings: AppSettingsState = {
 	relayUrls: [''],
 	relayAuthToken: '',
 	showBroadcastToggle: false,
+	useIgnoreFiles: true,
 }
 export const localSettingStore = new LazyStore(SETTING_FILE, {
 	autoSave: true,
@@ -1,15 +1,227 @@
 import { invoke } from '@tauri-apps/api/core'
 
+export interface ShareStarted {
+	shareId: string
+	ticket: string
+	hash: string
+	size: number
+	entryType: string
+	fileCount: number
+	// Differs from `get_endpoint_id` while another transfer holds the identity.
+	endpointId: string
+	skippedLinks: SkippedLink[]
+	skipped: SkippedEntry[]
+}
+
+export type SymlinkPolicy = 'skip' | 'follow' | 'preserve'
+
+export interface SkippedLink {
+	name: string
+	reason: 'policy' | 'loop' | 'broken' | 'outsideShare'
+}
+
+export interface SkippedEntry {
+	name: string
+	reason: 'unreadable' | 'invalidName' | 'unsupported' | 'noFiles'
+}
+
+export interface ReceiveResult {
+	message: string
+	filePath: string
+	senderName: string | null
+	totalFiles: number
+	payloadSize: number
+	bytesFetched: number
+	durationMs: number
+	averageSpeedBps: number
+	conflicts: ExportConflict[]
+	files: { name: string; path: string; size: number }[]
+	connectionPath: 'direct' | 'relay' | 'local' | 'unknown'
+	wasCached: boolean
+	mirror?: MirrorDiff
+}
+
+// Optional settings of `receive_file` and `resume_receive`.
+export interface ReceiveOptions {
+	conflictPolicy?: ConflictPolicy
+	selection?: EntrySelector[]
+	mirror?: MirrorOptions
+	latest?: boolean
+	password?: string
+	maxRate?: number
+}
+
+export interface MirrorOptions {
+	deleteExtraneous: boolean
+}
+
+// Entry names, relative to the output directory.
+export interface MirrorDiff {
+	added: string[]
+	updated: string[]
+	unchanged: string[]
+	deleted: string[]
+}
+
+// Payloads of engine transfer events; `event` repeats the event name.
+export interface ProgressEvent {
+	event: 'transfer-progress' | 'receive-progress'
+	bytesTransferred: number
+	totalBytes: number
+	speedBps: number
+}
+
+export interface ExportProgressEvent {
+	event: 'export-progress'
+	fileName: string
+	fileIndex: number
+	totalFiles: number
+	bytesCopied: number
+	totalBytes: number
+}
+
+export interface ActiveConnectionCountEvent {
+	event: 'active-connection-count'
+	count: number
+}
+
+export interface ShareExpiredEvent {
+	event: 'share-expired'
+	reason: 'timeLimit' | 'downloadLimit'
+}
+
+// Sent for each new receiver of a share started with `confirmTimeoutSecs`; the receiver waits
+// for `respond_connection_request`.
+export interface ConnectionRequestEvent {
+	event: 'connection-request'
+	id: number
+	endpointId: string
+	contactName: string | null
+}
+
+// Upload rate limits of a share in bytes per second; null means unlimited.
+export interface RateLimits {
+	total: number | null
+	perReceiver: number | null
+}
+
+export interface ReceiverProgress {
+	connectionId: number
+	endpointId: string
+	contactName: string | null
+	bytesSent: number
+	totalBytes: number
+	speedBps: number
+	status: 'active' | 'completed' | 'aborted'
+}
+
+// Progress of one receiver of a share.
+export type ReceiverProgressEvent = ReceiverProgress & {
+	event: 'receiver-progress'
+}
+
+export interface FileNamesEvent {
+	event: 'receive-file-names'
+	names: string[]
+}
+
+export type ConflictPolicy =
+	| 'rename'
+	| 'overwrite'
+	| 'skipIfIdentical'
+	| 'skip'
+	| 'ask'
+
+export interface ExportConflict {
+	original: string
+	resolved: string
+	action: 'renamed' | 'overwritten' | 'skipped' | 'identical'
+}
+
+export interface ConflictsEvent {
+	event: 'receive-conflicts'
+	conflicts: ExportConflict[]
+}
+
+// Sent when the conflict policy is 'ask'; the export waits for `resolve_conflict`.
+export interface ExportConflictEvent {
+	event: 'export-conflict'
+	id: number
+	name: string
+	path: string
+	existingSize: number
+	incomingSize: number
+}
+
+// Picks collection entries to receive: an exact path (or directory prefix), a glob, or a position.
+export type EntrySelector = { name: string } | { glob: string } | { index: number }
+
+// Events of a share additionally carry the ID of the share they belong to.
+export type ShareEvent<T = { event: string }> = T & { shareId: string }
+
+// Events of a receive additionally carry the ticket it was started with.
+export type ReceiveEvent<T = { event: string }> = T & { ticket: string }
+
+export interface Contact {
+	endpointId: string
+	name: string
+	addedAt: number
+}
+
 export interface TauriCommands {
-	start_sharing: (path: string) => Promise<string>
-	stop_sharing: () => Promise<void>
-	receive_file: (ticket: string) => Promise<string>
-	get_sharing_status: () => Promise<string | null>
+	start_sharing: (path: string) => Promise<ShareStarted>
+	stop_sharing: (shareId: string) => Promise<void>
+	receive_file: (
+		ticket: string,
+		selection?: EntrySelector[]
+	) => Promise<ReceiveResult>
+	get_sharing_status: (shareId: string) => Promise<string | null>
+	get_endpoint_id: () => Promise<string>
+	rotate_identity: () => Promise<string>
+	export_identity: () => Promise<string>
+	list_contacts: () => Promise<Contact[]>
+	add_contact: (endpointId: string, name: string) => Promise<Contact>
+	remove_contact: (endpointId: string) => Promise<void>
+	resolve_conflict: (
+		id: number,
+		policy: ConflictPolicy,
+		applyToAll: boolean
+	) => Promise<void>
+	respond_connection_request: (id: number, accept: boolean) => Promise<void>
+	list_share_receivers: (shareId: string) => Promise<ReceiverProgress[]>
+	set_share_rate_limits: (shareId: string, limits: RateLimits) => Promise<void>
+	// Pausing keeps the connection and the data received so far; the receive emits
+	// `receive-paused` and `receive-resumed`.
+	pause_receive: (ticket: string) => Promise<void>
+	continue_receive: (ticket: string) => Promise<void>
+	set_receive_rate_limit: (ticket: string, maxRate: number | null) => Promise<void>
 }
 
 export const tauriCommands: TauriCommands = {
 	start_sharing: (path: string) => invoke('start_sharing', { path }),
-	stop_sharing: () => invoke('stop_sharing'),
-	receive_file: (ticket: string) => invoke('receive_file', { ticket }),
-	get_sharing_status: () => invoke('get_sharing_status'),
+	stop_sharing: (shareId: string) => invoke('stop_sharing', { shareId }),
+	receive_file: (ticket: string, selection?: EntrySelector[]) =>
+		invoke('receive_file', { ticket, options: { selection } }),
+	get_sharing_status: (shareId: string) =>
+		invoke('get_sharing_status', { shareId }),
+	get_endpoint_id: () => invoke('get_endpoint_id'),
+	rotate_identity: () => invoke('rotate_identity'),
+	export_identity: () => invoke('export_identity'),
+	list_contacts: () => invoke('list_contacts'),
+	add_contact: (endpointId: string, name: string) =>
+		invoke('add_contact', { endpointId, name }),
+	remove_contact: (endpointId: string) =>
+		invoke('remove_contact', { endpointId }),
+	resolve_conflict: (id: number, policy: ConflictPolicy, applyToAll: boolean) =>
+		invoke('resolve_conflict', { id, decision: { policy, applyToAll } }),
+	respond_connection_request: (id: number, accept: boolean) =>
+		invoke('respond_connection_request', { id, accept }),
+	list_share_receivers: (shareId: string) =>
+		invoke('list_share_receivers', { shareId }),
+	set_share_rate_limits: (shareId: string, limits: RateLimits) =>
+		invoke('set_share_rate_limits', { shareId, limits }),
+	pause_receive: (ticket: string) => invoke('pause_receive', { ticket }),
+	continue_receive: (ticket: string) => invoke('continue_receive', { ticket }),
+	set_receive_rate_limit: (ticket: string, maxRate: number | null) =>
+		invoke('set_receive_rate_limit', { ticket, maxRate }),
 }
@@ -66,9 +66,26 @@ export function IndexPage() {
 			}
 		)
 
+		// Only one transfer at a time can present this device's endpoint ID; others use a
+		// random one that contacts and allow-lists of the other side do not know.
+		const unlistenIdentityPromise = listen<string>(
+			'identity-fell-back',
+			(event) => {
+				toastManager.add({
+					title: t('footer.identity.fellBackToastTitle'),
+					description:
+						event.payload === 'receive'
+							? t('footer.identity.fellBackToastReceive')
+							: t('footer.identity.fellBackToastSend'),
+					type: 'warning',
+				})
+			}
+		)
+
 		return () => {
 			unlistenPromise.then((unlisten) => unlisten())
 			unlistenFellBackPromise.then((unlisten) => unlisten())
+			unlistenIdentityPromise.then((unlisten) => unlisten())
 		}
 	}, [setSelectedPath, setPathType, t])
 
@@ -1,6 +1,7 @@
 import MobileSettingSidebar from '../components/setting-sidebar/mobile-setting-sidebar'
 import { AutoUpdate } from '../components/settings/auto-update'
 import { BroadcastSettings } from '../components/settings/broadcast'
+import { SharingSettings } from '../components/settings/sharing'
 import { SystemTray } from '../components/settings/system-tray/system-tray'
 import { useTranslation } from '../i18n'
 
@@ -12,6 +13,7 @@ export function SettingGeneralPage() {
 				{t('settings.navItems.general')}
 			</MobileSettingSidebar>
 			<BroadcastSettings />
+			<SharingSettings />
 			<SystemTray />
 			<AutoUpdate />
 		</>
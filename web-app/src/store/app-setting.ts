@@ -18,6 +18,7 @@ export type AppSettingsState = {
 	relayUrls: string[]
 	relayAuthToken: string
 	showBroadcastToggle: boolean
+	useIgnoreFiles: boolean
 }
 
 export type AppSettingsActions = {
@@ -33,6 +34,7 @@ export type AppSettingsActions = {
 	setRelayUrls: (value: string[]) => void
 	setRelayAuthToken: (value: string) => void
 	setShowBroadcastToggle: (value: boolean) => void
+	setUseIgnoreFiles: (value: boolean) => void
 }
 
 export type AppSettings = AppSettingsState & AppSettingsActions
@@ -60,6 +62,7 @@ export const useAppSettingStore = create<AppSettings>()(
 			setRelayAuthToken: (value: string) => set({ relayAuthToken: value }),
 			setShowBroadcastToggle: (value: boolean) =>
 				set({ showBroadcastToggle: value }),
+			setUseIgnoreFiles: (value: boolean) => set({ useIgnoreFiles: value }),
 		}),
 		{
 			name: AppSettingsKey,
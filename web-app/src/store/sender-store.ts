@@ -10,6 +10,7 @@ export interface SenderStore {
 	viewState: SenderViewState
 
 	// Transfer data
+	shareId: string | null
 	ticket: string | null
 	selectedPaths: string[]
 	selectedPath: string | null
@@ -27,6 +28,7 @@ export interface SenderStore {
 
 	// Actions
 	setViewState: (state: SenderViewState) => void
+	setShareId: (shareId: string | null) => void
 	setTicket: (ticket: string | null) => void
 	setSelectedPaths: (paths: string[]) => void
 	addSelectedPaths: (paths: string[]) => void
@@ -53,6 +55,7 @@ export interface SenderStore {
 export const useSenderStore = create<SenderStore>()((set) => ({
 	// Initial state
 	viewState: 'IDLE',
+	shareId: null,
 	ticket: null,
 	selectedPaths: [],
 	selectedPath: null,
@@ -87,6 +90,7 @@ export const useSenderStore = create<SenderStore>()((set) => ({
 	setViewState: (viewState) => {
 		set({ viewState })
 	},
+	setShareId: (shareId) => set({ shareId }),
 	setTicket: (ticket) => set({ ticket }),
 	setSelectedPaths: (selectedPaths) =>
 		set({
@@ -172,6 +176,7 @@ export const useSenderStore = create<SenderStore>()((set) => ({
 		// })
 		set({
 			viewState: 'IDLE',
+			shareId: null,
 			ticket: null,
 			selectedPaths: [],
 			selectedPath: null,
@@ -30,6 +30,8 @@ export interface TicketPreviewMetadata {
 	thumbnail?: string
 	mimeType?: string
 	items?: TicketPreviewItem[]
+	senderName?: string
+	live?: boolean
 }
 
 export interface TicketPreviewItem {